@@ -1,5 +1,5 @@
 use clap::{crate_version, Arg, ArgGroup, Command};
-use clockwork_thread_program::state::{SerializableInstruction, Trigger};
+use clockwork_thread_program::state::{SerializableInstruction, ThreadFundingSettings, Trigger};
 use clockwork_webhook_program::state::HttpMethod;
 use solana_sdk::{pubkey::Pubkey, signature::Keypair};
 
@@ -18,6 +18,7 @@ pub enum CliCommand {
     // Crontab
     Crontab {
         schedule: String,
+        timezone: Option<String>,
     },
 
     // Delegation
@@ -82,6 +83,11 @@ pub enum CliCommand {
     ThreadDelete {
         id: String,
     },
+    ThreadFunding {
+        id: String,
+        settings: Option<ThreadFundingSettings>,
+        deposit: Option<u64>,
+    },
     ThreadGet {
         id: Option<String>,
         address: Option<Pubkey>,
@@ -99,6 +105,7 @@ pub enum CliCommand {
         id: String,
         rate_limit: Option<u64>,
         schedule: Option<String>,
+        timezone: Option<String>,
     },
 
     // Registry
@@ -196,6 +203,15 @@ pub fn app() -> Command<'static> {
                         .takes_value(true)
                         .required(true)
                         .help("The schedule to generate a cron table for"),
+                )
+                .arg(
+                    Arg::new("timezone")
+                        .long("timezone")
+                        .short('z')
+                        .value_name("TIMEZONE")
+                        .takes_value(true)
+                        .required(false)
+                        .help("The IANA timezone to evaluate the schedule in (defaults to UTC)"),
                 ),
         )
         .subcommand(
@@ -566,6 +582,15 @@ pub fn app() -> Command<'static> {
                                 .takes_value(true)
                                 .help("A cron-based trigger"),
                         )
+                        .arg(
+                            Arg::new("timezone")
+                                .long("timezone")
+                                .short('z')
+                                .value_name("TIMEZONE")
+                                .takes_value(true)
+                                .requires("cron")
+                                .help("The IANA timezone to evaluate the cron trigger in (defaults to UTC)"),
+                        )
                         .arg(
                             Arg::new("immediate")
                                 .long("immediate")
@@ -591,6 +616,61 @@ pub fn app() -> Command<'static> {
                             .help("The id of the thread to delete"),
                     ),
                 )
+                .subcommand(
+                    Command::new("funding")
+                        .about("Top up a thread from your fund when its balance runs low")
+                        .arg_required_else_help(true)
+                        .arg(
+                            Arg::new("id")
+                                .index(1)
+                                .takes_value(true)
+                                .required(true)
+                                .help("The id of the thread to fund"),
+                        )
+                        .arg(
+                            Arg::new("floor")
+                                .long("floor")
+                                .takes_value(true)
+                                .required_unless_present("disable")
+                                .help("The balance below which the thread is topped up, in lamports"),
+                        )
+                        .arg(
+                            Arg::new("target")
+                                .long("target")
+                                .takes_value(true)
+                                .required_unless_present("disable")
+                                .help("The balance the thread is topped up to, in lamports"),
+                        )
+                        .arg(
+                            Arg::new("allowance")
+                                .long("allowance")
+                                .takes_value(true)
+                                .required_unless_present("disable")
+                                .help("The maximum number of lamports drawn from your fund per period"),
+                        )
+                        .arg(
+                            Arg::new("period")
+                                .long("period")
+                                .takes_value(true)
+                                .required_unless_present("disable")
+                                .help("The length of an allowance period, in seconds"),
+                        )
+                        .arg(
+                            Arg::new("deposit")
+                                .long("deposit")
+                                .short('d')
+                                .takes_value(true)
+                                .required(false)
+                                .help("The number of lamports to deposit into your fund"),
+                        )
+                        .arg(
+                            Arg::new("disable")
+                                .long("disable")
+                                .takes_value(false)
+                                .conflicts_with_all(&["floor", "target", "allowance", "period"])
+                                .help("Stop topping up the thread"),
+                        ),
+                )
                 .subcommand(
                     Command::new("get")
                         .about("Lookup a thread")
@@ -669,6 +749,15 @@ pub fn app() -> Command<'static> {
                                 .takes_value(true)
                                 .required(false)
                                 .help("The cron schedule of the thread"),
+                        )
+                        .arg(
+                            Arg::new("timezone")
+                                .long("timezone")
+                                .short('z')
+                                .takes_value(true)
+                                .required(false)
+                                .requires("schedule")
+                                .help("The IANA timezone to evaluate the cron schedule in (defaults to UTC)"),
                         ),
                 ),
         )
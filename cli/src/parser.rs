@@ -1,7 +1,10 @@
 use std::{convert::TryFrom, fs, path::PathBuf, str::FromStr};
 
 use clap::ArgMatches;
-use clockwork_thread_program::state::{SerializableAccount, SerializableInstruction, Trigger};
+use clockwork_cron::Tz;
+use clockwork_thread_program::state::{
+    SerializableAccount, SerializableInstruction, ThreadFundingSettings, Trigger,
+};
 use clockwork_webhook_program::state::HttpMethod;
 use serde::{Deserialize as JsonDeserialize, Serialize as JsonSerialize};
 use solana_sdk::{
@@ -109,6 +112,7 @@ fn parse_config_command(matches: &ArgMatches) -> Result<CliCommand, CliError> {
 fn parse_crontab_command(matches: &ArgMatches) -> Result<CliCommand, CliError> {
     Ok(CliCommand::Crontab {
         schedule: parse_string("schedule", matches)?,
+        timezone: parse_timezone("timezone", matches)?,
     })
 }
 
@@ -206,6 +210,20 @@ fn parse_thread_command(matches: &ArgMatches) -> Result<CliCommand, CliError> {
         Some(("delete", matches)) => Ok(CliCommand::ThreadDelete {
             id: parse_string("id", matches)?,
         }),
+        Some(("funding", matches)) => Ok(CliCommand::ThreadFunding {
+            id: parse_string("id", matches)?,
+            settings: if matches.is_present("disable") {
+                None
+            } else {
+                Some(ThreadFundingSettings {
+                    allowance: parse_u64("allowance", matches)?,
+                    floor: parse_u64("floor", matches)?,
+                    period: parse_u64("period", matches)?,
+                    target: parse_u64("target", matches)?,
+                })
+            },
+            deposit: parse_u64("deposit", matches).ok(),
+        }),
         Some(("get", matches)) => Ok(CliCommand::ThreadGet {
             id: parse_string("id", matches).ok(),
             address: parse_pubkey("address", matches).ok(),
@@ -223,6 +241,7 @@ fn parse_thread_command(matches: &ArgMatches) -> Result<CliCommand, CliError> {
             id: parse_string("id", matches)?,
             rate_limit: parse_u64("rate_limit", matches).ok(),
             schedule: parse_string("schedule", matches).ok(),
+            timezone: parse_timezone("timezone", matches)?,
         }),
         _ => Err(CliError::CommandNotRecognized(
             matches.subcommand().unwrap().0.into(),
@@ -285,9 +304,17 @@ fn parse_trigger(matches: &ArgMatches) -> Result<Trigger, CliError> {
             size: 32,  // TODO
         });
     } else if matches.is_present("cron") {
-        return Ok(Trigger::Cron {
-            schedule: parse_string("cron", matches)?,
-            skippable: true,
+        let schedule = parse_string("cron", matches)?;
+        return Ok(match parse_timezone("timezone", matches)? {
+            None => Trigger::Cron {
+                schedule,
+                skippable: true,
+            },
+            Some(timezone) => Trigger::CronTz {
+                schedule,
+                skippable: true,
+                timezone,
+            },
         });
     } else if matches.is_present("now") {
         return Ok(Trigger::Now);
@@ -329,6 +356,16 @@ fn parse_string(arg: &str, matches: &ArgMatches) -> Result<String, CliError> {
         .to_string())
 }
 
+fn parse_timezone(arg: &str, matches: &ArgMatches) -> Result<Option<String>, CliError> {
+    match matches.value_of(arg) {
+        None => Ok(None),
+        Some(timezone) => {
+            Tz::from_str(timezone).map_err(|_err| CliError::BadParameter(arg.into()))?;
+            Ok(Some(timezone.to_string()))
+        }
+    }
+}
+
 pub fn _parse_i64(arg: &str, matches: &ArgMatches) -> Result<i64, CliError> {
     Ok(parse_string(arg, matches)?
         .parse::<i64>()
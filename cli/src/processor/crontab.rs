@@ -1,18 +1,21 @@
-use chrono::{DateTime, NaiveDateTime, Utc};
-use clockwork_cron::Schedule;
+use chrono::TimeZone;
+use clockwork_cron::{Schedule, Tz};
 use std::str::FromStr;
 
 use crate::{client::Client, errors::CliError};
 
-pub fn get(client: &Client, schedule: String) -> Result<(), CliError> {
+pub fn get(client: &Client, schedule: String, timezone: Option<String>) -> Result<(), CliError> {
     let clock = client.get_clock().unwrap();
     let schedule = Schedule::from_str(schedule.as_str()).unwrap();
+    let timezone = match timezone {
+        None => Tz::UTC,
+        Some(timezone) => {
+            Tz::from_str(&timezone).map_err(|_err| CliError::BadParameter(timezone))?
+        }
+    };
 
     let mut i = 0;
-    for t in schedule.after(&DateTime::<Utc>::from_naive_utc_and_offset(
-        NaiveDateTime::from_timestamp_opt(clock.unix_timestamp, 0).unwrap(),
-        Utc,
-    )) {
+    for t in schedule.after(&timezone.timestamp_opt(clock.unix_timestamp, 0).unwrap()) {
         println!("{:#?}", t);
         i += 1;
         if i > 8 {
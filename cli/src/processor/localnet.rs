@@ -249,6 +249,7 @@ fn create_threads(client: &Client, mint_pubkey: Pubkey) -> Result<()> {
                 schedule: "0 * * * * * *".into(),
                 skippable: true,
             },
+            limits: None,
         }
         .data(),
     };
@@ -283,6 +284,7 @@ fn create_threads(client: &Client, mint_pubkey: Pubkey) -> Result<()> {
                 schedule: "*/15 * * * * * *".into(),
                 skippable: true,
             },
+            limits: None,
         }
         .data(),
     };
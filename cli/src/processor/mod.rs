@@ -52,7 +52,7 @@ pub fn process(matches: &ArgMatches) -> Result<(), CliError> {
             epoch_thread,
             hasher_thread,
         } => config::set(&client, admin, epoch_thread, hasher_thread),
-        CliCommand::Crontab { schedule } => crontab::get(&client, schedule),
+        CliCommand::Crontab { schedule, timezone } => crontab::get(&client, schedule, timezone),
         CliCommand::DelegationCreate { worker_id } => delegation::create(&client, worker_id),
         CliCommand::DelegationDeposit {
             amount,
@@ -107,6 +107,11 @@ pub fn process(matches: &ArgMatches) -> Result<(), CliError> {
             trigger,
         } => thread::create(&client, id, vec![kickoff_instruction], trigger),
         CliCommand::ThreadDelete { id } => thread::delete(&client, id),
+        CliCommand::ThreadFunding {
+            id,
+            settings,
+            deposit,
+        } => thread::funding(&client, id, settings, deposit),
         CliCommand::ThreadPause { id } => thread::pause(&client, id),
         CliCommand::ThreadResume { id } => thread::resume(&client, id),
         CliCommand::ThreadReset { id } => thread::reset(&client, id),
@@ -118,7 +123,8 @@ pub fn process(matches: &ArgMatches) -> Result<(), CliError> {
             id,
             rate_limit,
             schedule,
-        } => thread::update(&client, id, rate_limit, schedule),
+            timezone,
+        } => thread::update(&client, id, rate_limit, schedule, timezone),
         CliCommand::RegistryGet => registry::get(&client),
         CliCommand::RegistryUnlock => registry::unlock(&client),
         CliCommand::WebhookCreate {
@@ -1,8 +1,8 @@
 use anchor_lang::{
-    solana_program::{instruction::Instruction, system_program},
+    solana_program::{instruction::Instruction, system_instruction, system_program},
     InstructionData, AccountDeserialize, ToAccountMetas
 };
-use clockwork_thread_program::state::{SerializableInstruction, Thread, ThreadSettings, Trigger, VersionedThread};
+use clockwork_thread_program::state::{Fund, SerializableInstruction, Thread, ThreadFundingSettings, ThreadSettings, Trigger, VersionedThread};
 use clockwork_utils::CrateInfo;
 use solana_sdk::pubkey::Pubkey;
 
@@ -41,6 +41,7 @@ pub fn create(
             id: id.into_bytes(),
             instructions,
             trigger,
+            limits: None,
         }
         .data(),
     };
@@ -64,10 +65,75 @@ pub fn delete(client: &Client, id: String) -> Result<(), CliError> {
     Ok(())
 }
 
+pub fn funding(
+    client: &Client,
+    id: String,
+    settings: Option<ThreadFundingSettings>,
+    deposit: Option<u64>,
+) -> Result<(), CliError> {
+    let thread_pubkey = Thread::pubkey(client.payer_pubkey(), id.into_bytes());
+    let fund_pubkey = Fund::pubkey(client.payer_pubkey());
+    let mut ixs = vec![];
+
+    // Create the fund if it doesn't exist yet, otherwise deposit into it.
+    let deposit = deposit.unwrap_or(0);
+    if client.get_account(&fund_pubkey).is_err() {
+        if settings.is_some() || deposit.gt(&0) {
+            ixs.push(Instruction {
+                program_id: clockwork_thread_program::ID,
+                accounts: clockwork_thread_program::accounts::FundCreate {
+                    authority: client.payer_pubkey(),
+                    fund: fund_pubkey,
+                    payer: client.payer_pubkey(),
+                    system_program: system_program::ID,
+                }.to_account_metas(Some(false)),
+                data: clockwork_thread_program::instruction::FundCreate { amount: deposit }.data(),
+            });
+        }
+    } else if deposit.gt(&0) {
+        ixs.push(system_instruction::transfer(
+            &client.payer_pubkey(),
+            &fund_pubkey,
+            deposit,
+        ));
+    }
+
+    ixs.push(Instruction {
+        program_id: clockwork_thread_program::ID,
+        accounts: clockwork_thread_program::accounts::ThreadFundingUpdate {
+            authority: client.payer_pubkey(),
+            system_program: system_program::ID,
+            thread: thread_pubkey,
+        }.to_account_metas(Some(false)),
+        data: clockwork_thread_program::instruction::ThreadFundingUpdate { settings }.data(),
+    });
+    client.send_and_confirm(&ixs, &[client.payer()]).unwrap();
+    get(client, thread_pubkey)?;
+    Ok(())
+}
+
 pub fn get(client: &Client, address: Pubkey) -> Result<(), CliError> {
     let data = client.get_account_data(&address).unwrap();
     let thread = VersionedThread::try_deserialize(&mut data.as_slice()).unwrap();
-    println!("Address: {}\n{:#?}", address, thread);
+    let stats = thread.stats();
+    let memory = thread
+        .memory()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<String>>()
+        .concat();
+    println!(
+        "Address: {}\nRuns: {}\nExecs: {}\nFees paid: {}\nReimbursements: {}\nLast success: {}\nLast worker: {}\nMemory: {}\n{:#?}",
+        address,
+        stats.runs,
+        stats.execs,
+        stats.fees_paid,
+        stats.reimbursements,
+        stats.last_success_at.map_or("none".to_string(), |slot| slot.to_string()),
+        stats.last_worker.map_or("none".to_string(), |worker| worker.to_string()),
+        if memory.is_empty() { "none".to_string() } else { format!("0x{}", memory) },
+        thread
+    );
     Ok(())
 }
 
@@ -121,21 +187,37 @@ pub fn update(
     id: String,
     rate_limit: Option<u64>,
     schedule: Option<String>,
+    timezone: Option<String>,
 ) -> Result<(), CliError> {
     let thread_pubkey = Thread::pubkey(client.payer_pubkey(), id.into_bytes());
     let trigger = if let Some(schedule) = schedule {
-        Some(Trigger::Cron {
-            schedule,
-            skippable: true,
+        Some(match timezone {
+            None => Trigger::Cron {
+                schedule,
+                skippable: true,
+            },
+            Some(timezone) => Trigger::CronTz {
+                schedule,
+                skippable: true,
+                timezone,
+            },
         })
     } else {
         None
     };
     let settings = ThreadSettings {
+        failure_policy: None,
         fee: None,
         instructions: None,
+        instruction_patches: None,
+        limits: None,
+        lookup_tables: None,
+        memory_size: None,
         name: None,
+        priority_fee: None,
         rate_limit,
+        signers: None,
+        spending_limits: None,
         trigger,
     };
     let ix = Instruction {
@@ -8,12 +8,14 @@ pub struct Error {
 #[derive(Debug)]
 pub enum ErrorKind {
     Expression(String),
+    Timezone(String),
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.kind {
             ErrorKind::Expression(ref expr) => write!(f, "Invalid expression: {}", expr),
+            ErrorKind::Timezone(ref name) => write!(f, "Unknown timezone: {}", name),
         }
     }
 }
@@ -47,6 +47,9 @@ mod queries;
 mod schedule;
 mod specifier;
 mod time_unit;
+mod timezone;
+mod tzdata;
 
 pub use crate::schedule::Schedule;
 pub use crate::time_unit::TimeUnitSpec;
+pub use crate::timezone::{Tz, TzOffset};
@@ -1,5 +1,5 @@
-use chrono::offset::TimeZone;
-use chrono::{DateTime, Datelike, Timelike};
+use chrono::offset::{LocalResult, Offset, TimeZone};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::ops::Bound::{Included, Unbounded};
 
@@ -86,25 +86,24 @@ impl Schedule {
                             for second in
                                 self.fields.seconds.ordinals().range(second_range).cloned()
                             {
-                                let timezone = after.timezone();
-                                let candidate = timezone
-                                    .with_ymd_and_hms(
-                                        year as i32,
-                                        month,
-                                        day_of_month,
-                                        hour,
-                                        minute,
-                                        second,
-                                    )
-                                    .unwrap();
+                                let local =
+                                    NaiveDate::from_ymd_opt(year as i32, month, day_of_month)
+                                        .unwrap()
+                                        .and_hms_opt(hour, minute, second)
+                                        .unwrap();
                                 if !self
                                     .fields
                                     .days_of_week
                                     .ordinals()
-                                    .contains(&candidate.weekday().number_from_sunday())
+                                    .contains(&local.weekday().number_from_sunday())
                                 {
                                     continue 'day_loop;
                                 }
+                                let candidate = resolve_local(&after.timezone(), &local);
+                                if candidate <= *after {
+                                    // The local time was repeated by a DST overlap and its first occurrence has passed.
+                                    continue;
+                                }
                                 return Some(candidate);
                             }
                             query.reset_minute();
@@ -218,25 +217,24 @@ impl Schedule {
                                 .rev()
                                 .cloned()
                             {
-                                let timezone = before.timezone();
-                                let candidate = timezone
-                                    .with_ymd_and_hms(
-                                        year as i32,
-                                        month,
-                                        day_of_month,
-                                        hour,
-                                        minute,
-                                        second,
-                                    )
-                                    .unwrap();
+                                let local =
+                                    NaiveDate::from_ymd_opt(year as i32, month, day_of_month)
+                                        .unwrap()
+                                        .and_hms_opt(hour, minute, second)
+                                        .unwrap();
                                 if !self
                                     .fields
                                     .days_of_week
                                     .ordinals()
-                                    .contains(&candidate.weekday().number_from_sunday())
+                                    .contains(&local.weekday().number_from_sunday())
                                 {
                                     continue 'day_loop;
                                 }
+                                let candidate = resolve_local(&before.timezone(), &local);
+                                if candidate >= *before {
+                                    // The local time was skipped by a DST gap and was shifted past the reference moment.
+                                    continue;
+                                }
                                 return Some(candidate);
                             }
                             query.reset_minute();
@@ -336,6 +334,27 @@ impl Schedule {
     }
 }
 
+/// Resolves a local date and time in the given timezone to a single moment.
+///
+/// A local time skipped by a daylight saving gap is interpreted with the offset in effect before the gap,
+/// which shifts it forward by the length of the gap (e.g. 02:30 becomes 03:30 when clocks spring forward an hour).
+/// A local time repeated by a daylight saving overlap resolves to its first occurrence only.
+fn resolve_local<Z>(timezone: &Z, local: &NaiveDateTime) -> DateTime<Z>
+where
+    Z: TimeZone,
+{
+    match timezone.from_local_datetime(local) {
+        LocalResult::Single(datetime) => datetime,
+        LocalResult::Ambiguous(earliest, _latest) => earliest,
+        LocalResult::None => {
+            let offset = timezone
+                .offset_from_utc_datetime(&(*local - Duration::days(1)))
+                .fix();
+            timezone.from_utc_datetime(&(*local - offset))
+        }
+    }
+}
+
 impl Display for Schedule {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         write!(f, "{}", self.source)
@@ -0,0 +1,369 @@
+use chrono::offset::{LocalResult, Offset, TimeZone};
+use chrono::{Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime};
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+
+use crate::error::{Error, ErrorKind};
+use crate::tzdata::ZONES;
+
+/// An IANA timezone, such as `America/New_York`, for evaluating schedules in local time.
+///
+/// Each zone is described by its current POSIX TZ rule (a standard offset plus an optional daylight
+/// saving offset and yearly transition rules), looked up from a table embedded in this crate. This
+/// keeps timezone lookups allocation-free and deterministic in the Solana runtime. Since only the
+/// current rule is embedded, moments before a zone's most recent rule change are not historically accurate.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Tz {
+    name: &'static str,
+    std: ZoneOffset,
+    dst: Option<DaylightSaving>,
+}
+
+/// A named offset from UTC, in seconds east of Greenwich.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ZoneOffset {
+    abbreviation: &'static str,
+    utc_offset: i32,
+}
+
+/// The daylight saving offset of a zone and the rules for when it starts and ends each year.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct DaylightSaving {
+    offset: ZoneOffset,
+    start: Transition,
+    end: Transition,
+}
+
+/// A yearly transition moment, in the form "the nth weekday of a month, at a local time".
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Transition {
+    month: u32,
+    /// The week of the month (1-5), where 5 means the last such weekday of the month.
+    week: u32,
+    /// The day of the week, where 0 is Sunday.
+    weekday: u32,
+    /// The local time of day in seconds. May be negative or exceed 24 hours.
+    time: i32,
+}
+
+impl Tz {
+    /// The UTC timezone.
+    pub const UTC: Tz = Tz {
+        name: "UTC",
+        std: ZoneOffset {
+            abbreviation: "UTC",
+            utc_offset: 0,
+        },
+        dst: None,
+    };
+
+    /// Returns the IANA name of the timezone.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn offset(&self, offset: ZoneOffset) -> TzOffset {
+        TzOffset {
+            tz: *self,
+            abbreviation: offset.abbreviation,
+            utc_offset: offset.utc_offset,
+        }
+    }
+}
+
+impl FromStr for Tz {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let index = ZONES
+            .binary_search_by(|(zone_name, _rule)| zone_name.cmp(&name))
+            .map_err(|_| ErrorKind::Timezone(name.into()))?;
+        let (name, rule) = ZONES[index];
+        let (std, dst) = RuleParser { input: rule }
+            .parse()
+            .ok_or_else(|| ErrorKind::Timezone(name.into()))?;
+        Ok(Tz { name, std, dst })
+    }
+}
+
+impl Debug for Tz {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl Display for Tz {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl TimeZone for Tz {
+    type Offset = TzOffset;
+
+    fn from_offset(offset: &TzOffset) -> Tz {
+        offset.tz
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<TzOffset> {
+        self.offset_from_local_datetime(&local.and_hms_opt(0, 0, 0).unwrap())
+    }
+
+    /// Local times skipped by a daylight saving gap have no offset,
+    /// and local times repeated by a daylight saving overlap have two (the earliest first).
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<TzOffset> {
+        let dst = match self.dst {
+            None => return LocalResult::Single(self.offset(self.std)),
+            Some(dst) => dst,
+        };
+        let is_valid = |offset: ZoneOffset| {
+            let utc = *local - Duration::seconds(offset.utc_offset as i64);
+            self.offset_from_utc_datetime(&utc).utc_offset == offset.utc_offset
+        };
+        let mut valid_offsets = [self.std, dst.offset]
+            .into_iter()
+            .filter(|offset| is_valid(*offset));
+        match (valid_offsets.next(), valid_offsets.next()) {
+            (None, _) => LocalResult::None,
+            (Some(offset), None) => LocalResult::Single(self.offset(offset)),
+            (Some(a), Some(b)) => {
+                // The larger offset maps to the earlier moment.
+                let (earliest, latest) = if a.utc_offset > b.utc_offset {
+                    (a, b)
+                } else {
+                    (b, a)
+                };
+                LocalResult::Ambiguous(self.offset(earliest), self.offset(latest))
+            }
+        }
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> TzOffset {
+        self.offset_from_utc_datetime(&utc.and_hms_opt(0, 0, 0).unwrap())
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> TzOffset {
+        let dst = match self.dst {
+            None => return self.offset(self.std),
+            Some(dst) => dst,
+        };
+
+        // The start of daylight saving is given in standard time, and the end in daylight saving time.
+        let year = (*utc + Duration::seconds(self.std.utc_offset as i64)).year();
+        let start = dst.start.moment(year, self.std.utc_offset);
+        let end = dst.end.moment(year, dst.offset.utc_offset);
+        let is_dst = if start < end {
+            start <= *utc && *utc < end
+        } else {
+            // In the southern hemisphere, daylight saving spans the new year.
+            *utc < end || start <= *utc
+        };
+        if is_dst {
+            self.offset(dst.offset)
+        } else {
+            self.offset(self.std)
+        }
+    }
+}
+
+impl Transition {
+    /// Returns the UTC moment of the transition in the given year.
+    fn moment(&self, year: i32, utc_offset: i32) -> NaiveDateTime {
+        let first_of_month = NaiveDate::from_ymd_opt(year, self.month, 1).unwrap();
+        let first_weekday = first_of_month.weekday().num_days_from_sunday();
+        let mut day = 1 + (self.weekday + 7 - first_weekday) % 7 + (self.week - 1) * 7;
+        while NaiveDate::from_ymd_opt(year, self.month, day).is_none() {
+            day -= 7;
+        }
+        NaiveDate::from_ymd_opt(year, self.month, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            + Duration::seconds(self.time as i64 - utc_offset as i64)
+    }
+}
+
+/// The offset of a moment in a [Tz](struct.Tz.html).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct TzOffset {
+    tz: Tz,
+    abbreviation: &'static str,
+    utc_offset: i32,
+}
+
+impl TzOffset {
+    /// Returns the abbreviation of the offset, such as `EST` or `EDT`.
+    pub fn abbreviation(&self) -> &'static str {
+        self.abbreviation
+    }
+}
+
+impl Offset for TzOffset {
+    fn fix(&self) -> FixedOffset {
+        FixedOffset::east_opt(self.utc_offset).unwrap()
+    }
+}
+
+impl Debug for TzOffset {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.abbreviation)
+    }
+}
+
+impl Display for TzOffset {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.abbreviation)
+    }
+}
+
+/// A parser for the POSIX TZ rules in the embedded zone table, e.g. `EST5EDT,M3.2.0,M11.1.0`.
+/// Only the `Mm.w.d` transition format is supported, since it is the only one used by the table.
+struct RuleParser {
+    input: &'static str,
+}
+
+impl RuleParser {
+    fn parse(mut self) -> Option<(ZoneOffset, Option<DaylightSaving>)> {
+        // POSIX offsets are given in hours west of Greenwich.
+        let std = ZoneOffset {
+            abbreviation: self.abbreviation()?,
+            utc_offset: -self.time()?,
+        };
+        if self.input.is_empty() {
+            return Some((std, None));
+        }
+
+        // The daylight saving offset defaults to one hour ahead of standard time.
+        let abbreviation = self.abbreviation()?;
+        let utc_offset = if self.input.starts_with(',') {
+            std.utc_offset + 3600
+        } else {
+            -self.time()?
+        };
+        self.expect(',')?;
+        let start = self.transition()?;
+        self.expect(',')?;
+        let end = self.transition()?;
+        if !self.input.is_empty() {
+            return None;
+        }
+        Some((
+            std,
+            Some(DaylightSaving {
+                offset: ZoneOffset {
+                    abbreviation,
+                    utc_offset,
+                },
+                start,
+                end,
+            }),
+        ))
+    }
+
+    fn abbreviation(&mut self) -> Option<&'static str> {
+        if let Some(input) = self.input.strip_prefix('<') {
+            let len = input.find('>')?;
+            self.input = &input[len + 1..];
+            Some(&input[..len])
+        } else {
+            let len = self
+                .input
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(self.input.len());
+            let abbreviation = &self.input[..len];
+            self.input = &self.input[len..];
+            (len > 0).then_some(abbreviation)
+        }
+    }
+
+    fn transition(&mut self) -> Option<Transition> {
+        self.expect('M')?;
+        let month = self.number()? as u32;
+        self.expect('.')?;
+        let week = self.number()? as u32;
+        self.expect('.')?;
+        let weekday = self.number()? as u32;
+        let time = if self.input.starts_with('/') {
+            self.expect('/')?;
+            self.time()?
+        } else {
+            7200
+        };
+        ((1..=12).contains(&month) && (1..=5).contains(&week) && weekday < 7).then_some(
+            Transition {
+                month,
+                week,
+                weekday,
+                time,
+            },
+        )
+    }
+
+    /// Parses a signed time of the form `[+-]h[:mm[:ss]]` into seconds.
+    fn time(&mut self) -> Option<i32> {
+        let sign = if self.input.starts_with('-') {
+            self.expect('-')?;
+            -1
+        } else {
+            if self.input.starts_with('+') {
+                self.expect('+')?;
+            }
+            1
+        };
+        let mut seconds = self.number()? * 3600;
+        for unit in [60, 1] {
+            if !self.input.starts_with(':') {
+                break;
+            }
+            self.expect(':')?;
+            seconds += self.number()? * unit;
+        }
+        Some(sign * seconds)
+    }
+
+    fn number(&mut self) -> Option<i32> {
+        let len = self
+            .input
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.input.len());
+        let number = self.input[..len].parse().ok()?;
+        self.input = &self.input[len..];
+        Some(number)
+    }
+
+    fn expect(&mut self, c: char) -> Option<()> {
+        self.input = self.input.strip_prefix(c)?;
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_zone_table_is_sorted() {
+        assert!(ZONES.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_zone_table_rules_parse() {
+        for (name, _rule) in ZONES {
+            assert!(Tz::from_str(name).is_ok(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let tz = Tz::from_str("America/St_Johns").unwrap();
+        assert_eq!(-12600, tz.std.utc_offset);
+        assert_eq!(-9000, tz.dst.unwrap().offset.utc_offset);
+
+        let tz = Tz::from_str("Asia/Gaza").unwrap();
+        assert_eq!(180000, tz.dst.unwrap().start.time);
+
+        let tz = Tz::from_str("America/Sao_Paulo").unwrap();
+        assert_eq!("-03", tz.std.abbreviation);
+        assert!(tz.dst.is_none());
+    }
+}
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
     use chrono::*;
-    use clockwork_cron::{Schedule, TimeUnitSpec};
+    use clockwork_cron::{Schedule, TimeUnitSpec, Tz};
     use std::str::FromStr;
 
     #[test]
@@ -210,4 +210,115 @@ mod tests {
         assert!(schedule.minutes().is_all());
         assert!(schedule.seconds().is_all());
     }
+
+    #[test]
+    fn test_timezone_across_dst() {
+        let schedule = Schedule::from_str("0 0 9 * * * *").unwrap();
+        let tz = Tz::from_str("America/New_York").unwrap();
+        let starting_date = Utc
+            .with_ymd_and_hms(2024, 3, 8, 15, 0, 0)
+            .unwrap()
+            .with_timezone(&tz);
+        let mut events = schedule.after(&starting_date);
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 3, 9, 14, 0, 0).unwrap(),
+            events.next().unwrap()
+        );
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 3, 10, 13, 0, 0).unwrap(),
+            events.next().unwrap()
+        );
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 3, 11, 13, 0, 0).unwrap(),
+            events.next().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_timezone_dst_gap() {
+        // 02:30 does not exist on 2024-03-10 in New York, so it is shifted forward to 03:30 EDT.
+        let schedule = Schedule::from_str("0 30 2 * * * *").unwrap();
+        let tz = Tz::from_str("America/New_York").unwrap();
+        let starting_date = Utc
+            .with_ymd_and_hms(2024, 3, 9, 12, 0, 0)
+            .unwrap()
+            .with_timezone(&tz);
+        let mut events = schedule.after(&starting_date);
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 3, 10, 7, 30, 0).unwrap(),
+            events.next().unwrap()
+        );
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 3, 11, 6, 30, 0).unwrap(),
+            events.next().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_timezone_dst_overlap() {
+        // 01:30 occurs twice on 2024-11-03 in New York, but only the first occurrence (EDT) fires.
+        let schedule = Schedule::from_str("0 30 1 * * * *").unwrap();
+        let tz = Tz::from_str("America/New_York").unwrap();
+        let starting_date = Utc
+            .with_ymd_and_hms(2024, 11, 2, 12, 0, 0)
+            .unwrap()
+            .with_timezone(&tz);
+        let mut events = schedule.after(&starting_date);
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 11, 3, 5, 30, 0).unwrap(),
+            events.next().unwrap()
+        );
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 11, 4, 6, 30, 0).unwrap(),
+            events.next().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_timezone_southern_hemisphere() {
+        let schedule = Schedule::from_str("0 0 9 * * * *").unwrap();
+        let tz = Tz::from_str("Australia/Sydney").unwrap();
+        let starting_date = Utc
+            .with_ymd_and_hms(2024, 4, 5, 12, 0, 0)
+            .unwrap()
+            .with_timezone(&tz);
+        let mut events = schedule.after(&starting_date);
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 4, 5, 22, 0, 0).unwrap(),
+            events.next().unwrap()
+        );
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 4, 6, 23, 0, 0).unwrap(),
+            events.next().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_timezone_offsets() {
+        let tz = Tz::from_str("Europe/Dublin").unwrap();
+        let summer = Utc
+            .with_ymd_and_hms(2024, 7, 1, 0, 0, 0)
+            .unwrap()
+            .with_timezone(&tz);
+        let winter = Utc
+            .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+            .unwrap()
+            .with_timezone(&tz);
+        assert_eq!(3600, summer.offset().fix().local_minus_utc());
+        assert_eq!(0, winter.offset().fix().local_minus_utc());
+
+        let tz = Tz::from_str("Asia/Kolkata").unwrap();
+        let moment = Utc
+            .with_ymd_and_hms(2024, 7, 1, 0, 0, 0)
+            .unwrap()
+            .with_timezone(&tz);
+        assert_eq!(19800, moment.offset().fix().local_minus_utc());
+        assert_eq!("UTC", Tz::UTC.name());
+    }
+
+    #[test]
+    fn test_unknown_timezone() {
+        assert!(Tz::from_str("Mars/Olympus_Mons").is_err());
+        assert!(Tz::from_str("america/new_york").is_err());
+    }
 }
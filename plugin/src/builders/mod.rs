@@ -1,5 +1,9 @@
 mod pool_rotation;
 mod thread_exec;
+mod thread_exec_intent;
+mod thread_report_failure;
 
 pub use pool_rotation::*;
 pub use thread_exec::*;
+pub use thread_exec_intent::*;
+pub use thread_report_failure::*;
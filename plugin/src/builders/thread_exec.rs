@@ -1,9 +1,12 @@
 use std::sync::Arc;
 
 use anchor_lang::{InstructionData, ToAccountMetas};
-use clockwork_thread_program::state::{VersionedThread, Trigger};
+use clockwork_thread_program::state::{Fund, PriorityFee, Trigger, VersionedThread};
 use clockwork_network_program::state::Worker;
-use clockwork_utils::thread::PAYER_PUBKEY;
+use clockwork_utils::thread::{
+    thread_signer_index, SerializableInstruction, FEE_PUBKEY, PAYER_PUBKEY, THREAD_PUBKEY,
+    WORKER_PUBKEY,
+};
 use log::info;
 use solana_account_decoder::UiAccountEncoding;
 use solana_client::{
@@ -15,13 +18,22 @@ use solana_geyser_plugin_interface::geyser_plugin_interface::{
     GeyserPluginError, Result as PluginResult,
 };
 use solana_program::{
+    address_lookup_table::state::AddressLookupTable,
+    address_lookup_table_account::AddressLookupTableAccount,
+    hash::Hash,
     instruction::{AccountMeta, Instruction},
+    message::{v0, VersionedMessage},
     pubkey::Pubkey,
+    sysvar,
 };
 use solana_sdk::{
-    account::Account, commitment_config::CommitmentConfig,
-    compute_budget::ComputeBudgetInstruction, signature::Keypair, signer::Signer,
-    transaction::Transaction,
+    account::Account,
+    commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::InstructionError,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{TransactionError, VersionedTransaction},
 };
 
 /// Max byte size of a serialized transaction.
@@ -33,6 +45,14 @@ static TRANSACTION_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
 /// The buffer amount to add to transactions' compute units in case on-chain PDA derivations take more CUs than used in simulation.
 static TRANSACTION_COMPUTE_UNIT_BUFFER: u32 = 1000;
 
+/// The result of packing a thread's instructions into a transaction.
+pub enum ThreadExecTx {
+    /// A transaction whose instructions succeeded in simulation.
+    Executable(VersionedTransaction),
+    /// No instructions could be packed. Holds the error code of the failed simulation, if it had one.
+    Failed(Option<u32>),
+}
+
 pub async fn build_thread_exec_tx(
     client: Arc<RpcClient>,
     payer: &Keypair,
@@ -40,17 +60,21 @@ pub async fn build_thread_exec_tx(
     thread: VersionedThread,
     thread_pubkey: Pubkey,
     worker_id: u64,
-) -> PluginResult<Option<Transaction>> {
+) -> PluginResult<ThreadExecTx> {
     // Grab the thread and relevant data.
     let now = std::time::Instant::now();
     let blockhash = client.get_latest_blockhash().await.unwrap();
     let signatory_pubkey = payer.pubkey();
     let worker_pubkey = Worker::pubkey(worker_id);
+    let lookup_tables = get_lookup_tables(&client, thread.lookup_tables()).await;
+    let priority_fee = thread.priority_fee();
+    let compute_unit_price = get_compute_unit_price(&client, thread_pubkey, &priority_fee).await;
 
     // Build the first instruction of the transaction.
     let first_instruction = if thread.next_instruction().is_some() {
         build_exec_ix(
             thread.clone(),
+            thread.next_instruction(),
             thread_pubkey,
             signatory_pubkey,
             worker_pubkey,
@@ -65,19 +89,32 @@ pub async fn build_thread_exec_tx(
     };
 
     // Simulate the transaction and pack as many instructions as possible until we hit mem/cpu limits.
-    // TODO Migrate to versioned transactions.
-    let mut ixs: Vec<Instruction> = vec![
-        ComputeBudgetInstruction::set_compute_unit_limit(TRANSACTION_COMPUTE_UNIT_LIMIT),
-        first_instruction,
-    ];
+    let mut ixs: Vec<Instruction> = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+        TRANSACTION_COMPUTE_UNIT_LIMIT,
+    )];
+    if compute_unit_price.gt(&0) {
+        ixs.push(ComputeBudgetInstruction::set_compute_unit_price(
+            compute_unit_price,
+        ));
+    }
+    ixs.push(first_instruction);
     let mut successful_ixs: Vec<Instruction> = vec![];
     let mut units_consumed: Option<u64> = None;
+    let mut error_code: Option<u32> = None;
     loop {
-        let mut sim_tx = Transaction::new_with_payer(&ixs, Some(&signatory_pubkey));
-        sim_tx.sign(&[payer], blockhash);
+        // If a speculative batch of instructions cannot be packed, retry with only the first of them.
+        let is_speculative = !successful_ixs.is_empty() && ixs.len() > successful_ixs.len() + 1;
+        let sim_tx = match build_versioned_tx(payer, &ixs, &lookup_tables, blockhash) {
+            Err(_err) => break,
+            Ok(sim_tx) => sim_tx,
+        };
 
         // Exit early if the transaction exceeds the size limit.
-        if sim_tx.message_data().len() > TRANSACTION_MESSAGE_SIZE_LIMIT {
+        if sim_tx.message.serialize().len() > TRANSACTION_MESSAGE_SIZE_LIMIT {
+            if is_speculative {
+                ixs.truncate(successful_ixs.len() + 1);
+                continue;
+            }
             break;
         }
 
@@ -121,13 +158,22 @@ pub async fn build_thread_exec_tx(
                     }
                     _ => {}
                 }
+                if is_speculative {
+                    ixs.truncate(successful_ixs.len() + 1);
+                    continue;
+                }
                 break;
             }
 
             // If the simulation was successful, pack the ix into the tx.
             Ok(response) => {
                 if response.value.err.is_some() {
+                    if is_speculative {
+                        ixs.truncate(successful_ixs.len() + 1);
+                        continue;
+                    }
                     if successful_ixs.is_empty() {
+                        error_code = response.value.err.as_ref().and_then(custom_error_code);
                         info!(
                             "slot: {} thread: {} simulation_error: \"{}\" logs: {:?}",
                             slot,
@@ -158,12 +204,30 @@ pub async fn build_thread_exec_tx(
                                             .execs_since_slot
                                             .lt(&sim_thread.rate_limit())
                                         {
-                                            ixs.push(build_exec_ix(
-                                                sim_thread,
-                                                thread_pubkey,
-                                                signatory_pubkey,
-                                                worker_pubkey,
-                                            ));
+                                            // Speculatively pack the queued dynamic instructions along with the next instruction,
+                                            // assuming none of them queue further instructions.
+                                            let execs_remaining = sim_thread
+                                                .rate_limit()
+                                                .saturating_sub(exec_context.execs_since_slot);
+                                            let next_instructions =
+                                                std::iter::once(sim_thread.next_instruction())
+                                                    .chain(
+                                                        sim_thread
+                                                            .dynamic_instructions()
+                                                            .into_iter()
+                                                            .map(Some),
+                                                    )
+                                                    .take(execs_remaining as usize)
+                                                    .collect::<Vec<_>>();
+                                            for next_instruction in next_instructions {
+                                                ixs.push(build_exec_ix(
+                                                    sim_thread.clone(),
+                                                    next_instruction,
+                                                    thread_pubkey,
+                                                    signatory_pubkey,
+                                                    worker_pubkey,
+                                                ));
+                                            }
                                         } else {
                                             // Exit early if the thread has reached its rate limit.
                                             break;
@@ -183,12 +247,13 @@ pub async fn build_thread_exec_tx(
     // If there were no successful instructions, then exit early. There is nothing to do.
     // Alternatively, exit early if only the kickoff instruction (and no execs) succeeded.
     if successful_ixs.is_empty() {
-        return Ok(None);
+        return Ok(ThreadExecTx::Failed(error_code));
     }
 
     // Set the transaction's compute unit limit to be exactly the amount that was used in simulation.
+    let mut units_committed = TRANSACTION_COMPUTE_UNIT_LIMIT;
     if let Some(units_consumed) = units_consumed {
-        let units_committed = std::cmp::min(
+        units_committed = std::cmp::min(
             (units_consumed as u32) + TRANSACTION_COMPUTE_UNIT_BUFFER,
             TRANSACTION_COMPUTE_UNIT_LIMIT,
         );
@@ -198,9 +263,20 @@ pub async fn build_thread_exec_tx(
         );
     }
 
+    // Lower the compute unit price if needed, so the priority fee stays within the thread's per-transaction cap.
+    if compute_unit_price.gt(&0) {
+        let max_compute_unit_price =
+            (priority_fee.max_fee_per_tx as u128 * 1_000_000 / units_committed as u128) as u64;
+        _ = std::mem::replace(
+            &mut successful_ixs[1],
+            ComputeBudgetInstruction::set_compute_unit_price(
+                compute_unit_price.min(max_compute_unit_price),
+            ),
+        );
+    }
+
     // Build and return the signed transaction.
-    let mut tx = Transaction::new_with_payer(&successful_ixs, Some(&signatory_pubkey));
-    tx.sign(&[payer], blockhash);
+    let tx = build_versioned_tx(payer, &successful_ixs, &lookup_tables, blockhash)?;
     info!(
         "slot: {:?} thread: {:?} sim_duration: {:?} instruction_count: {:?} compute_units: {:?} tx_sig: {:?}",
         slot,
@@ -210,7 +286,82 @@ pub async fn build_thread_exec_tx(
         units_consumed,
         tx.signatures[0]
     );
-    Ok(Some(tx))
+    Ok(ThreadExecTx::Executable(tx))
+}
+
+/// Returns the custom program error code of a failed transaction, if it had one.
+fn custom_error_code(err: &TransactionError) -> Option<u32> {
+    match err {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(*code),
+        _ => None,
+    }
+}
+
+/// Prices the thread's transactions at the median of recent prioritization fees, capped by the thread's max compute unit price.
+async fn get_compute_unit_price(
+    client: &RpcClient,
+    thread_pubkey: Pubkey,
+    priority_fee: &PriorityFee,
+) -> u64 {
+    if priority_fee.max_compute_unit_price.eq(&0) || priority_fee.max_fee_per_tx.eq(&0) {
+        return 0;
+    }
+    let mut fees = match client
+        .get_recent_prioritization_fees(&[thread_pubkey])
+        .await
+    {
+        Err(_err) => return 0,
+        Ok(fees) => fees
+            .iter()
+            .map(|fee| fee.prioritization_fee)
+            .collect::<Vec<u64>>(),
+    };
+    if fees.is_empty() {
+        return 0;
+    }
+    fees.sort_unstable();
+    fees[fees.len() / 2].min(priority_fee.max_compute_unit_price)
+}
+
+/// Compiles and signs a v0 transaction, using the address lookup tables to compress its account keys.
+fn build_versioned_tx(
+    payer: &Keypair,
+    ixs: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    blockhash: Hash,
+) -> PluginResult<VersionedTransaction> {
+    let message = v0::Message::try_compile(&payer.pubkey(), ixs, lookup_tables, blockhash)
+        .map_err(|err| {
+            GeyserPluginError::Custom(format!("Failed to compile message: {}", err).into())
+        })?;
+    VersionedTransaction::try_new(VersionedMessage::V0(message), &[payer]).map_err(|err| {
+        GeyserPluginError::Custom(format!("Failed to sign transaction: {}", err).into())
+    })
+}
+
+/// Fetches the thread's address lookup tables. Tables which cannot be found or parsed are skipped.
+async fn get_lookup_tables(
+    client: &RpcClient,
+    lookup_table_pubkeys: Vec<Pubkey>,
+) -> Vec<AddressLookupTableAccount> {
+    if lookup_table_pubkeys.is_empty() {
+        return vec![];
+    }
+    match client.get_multiple_accounts(&lookup_table_pubkeys).await {
+        Err(_err) => vec![],
+        Ok(accounts) => lookup_table_pubkeys
+            .iter()
+            .zip(accounts)
+            .filter_map(|(key, account)| {
+                let account = account?;
+                let lookup_table = AddressLookupTable::deserialize(&account.data).ok()?;
+                Some(AddressLookupTableAccount {
+                    key: *key,
+                    addresses: lookup_table.addresses.to_vec(),
+                })
+            })
+            .collect(),
+    }
 }
 
 fn build_kickoff_ix(
@@ -234,6 +385,7 @@ fn build_kickoff_ix(
         VersionedThread::V2(_) => Instruction {
             program_id: clockwork_thread_program::ID,
             accounts: clockwork_thread_program::accounts::ThreadKickoff {
+                instructions: sysvar::instructions::ID,
                 signatory: signatory_pubkey,
                 thread: thread_pubkey,
                 worker: worker_pubkey,
@@ -243,39 +395,65 @@ fn build_kickoff_ix(
         },
     };
 
-    // If the thread's trigger is account-based, inject the triggering account.
-    match thread.trigger() {
+    // If the thread's trigger is account-based, inject the triggering accounts.
+    inject_trigger_accounts(&mut kickoff_ix, thread.trigger());
+
+    kickoff_ix
+}
+
+fn inject_trigger_accounts(kickoff_ix: &mut Instruction, trigger: Trigger) {
+    let account_pubkey = match trigger {
         Trigger::Account {
             address,
             offset: _,
             size: _,
-        } => kickoff_ix.accounts.push(AccountMeta {
-            pubkey: address,
-            is_signer: false,
-            is_writable: false,
-        }),
+        } => address,
         Trigger::Pyth {
             price_feed,
             equality: _,
             limit: _,
-        } => kickoff_ix.accounts.push(AccountMeta {
-            pubkey: price_feed,
-            is_signer: false,
-            is_writable: false,
-        }),
-        _ => {}
-    }
+        } => price_feed,
+        Trigger::PythPrice { price_feed, .. } => price_feed,
+        Trigger::Oracle { price_feed, .. } => price_feed,
+        Trigger::AccountValue {
+            address,
+            offset: _,
+            equality: _,
+            threshold: _,
+        } => address,
+        Trigger::ThreadCompleted { thread } => thread,
+        Trigger::TokenBalance { token_account, .. } => token_account,
+        Trigger::AccountComponents { address, .. } => address,
+        Trigger::Any { triggers } | Trigger::All { triggers } => {
+            // Inject the triggering accounts of every child trigger.
+            for trigger in triggers.iter() {
+                inject_trigger_accounts(kickoff_ix, trigger.clone());
+            }
+            return;
+        }
+        _ => return,
+    };
 
-    kickoff_ix
+    // Skip accounts which have already been injected by another child trigger.
+    if kickoff_ix.accounts.iter().any(|a| a.pubkey.eq(&account_pubkey)) {
+        return;
+    }
+    kickoff_ix.accounts.push(AccountMeta {
+        pubkey: account_pubkey,
+        is_signer: false,
+        is_writable: false,
+    });
 }
 
 fn build_exec_ix(
     thread: VersionedThread,
+    next_instruction: Option<SerializableInstruction>,
     thread_pubkey: Pubkey,
     signatory_pubkey: Pubkey,
     worker_pubkey: Pubkey,
 ) -> Instruction {
     // Build the instruction.
+    let signers = thread.signers();
     let mut exec_ix = match thread {
         VersionedThread::V1(_) => Instruction {
             program_id: clockwork_thread_program_v1::ID,
@@ -290,10 +468,15 @@ fn build_exec_ix(
             .to_account_metas(Some(true)),
             data: clockwork_thread_program_v1::instruction::ThreadExec {}.data(),
         },
-        VersionedThread::V2(_) => Instruction {
+        VersionedThread::V2(t) => Instruction {
             program_id: clockwork_thread_program::ID,
             accounts: clockwork_thread_program::accounts::ThreadExec {
                 fee: clockwork_network_program::state::Fee::pubkey(worker_pubkey),
+                fund: t
+                    .funding
+                    .as_ref()
+                    .map(|_| Fund::pubkey(t.current_authority())),
+                instructions: sysvar::instructions::ID,
                 pool: clockwork_network_program::state::Pool::pubkey(0),
                 signatory: signatory_pubkey,
                 thread: thread_pubkey,
@@ -304,17 +487,28 @@ fn build_exec_ix(
         },
     };
 
-    if let Some(next_instruction) = thread.next_instruction() {
+    if let Some(next_instruction) = next_instruction {
         // Inject the target program account.
         exec_ix.accounts.push(AccountMeta::new_readonly(
             next_instruction.program_id,
             false,
         ));
 
-        // Inject the worker pubkey as the dynamic "payer" account.
+        // Inject the worker pubkey as the dynamic "payer" account,
+        // and the thread, worker, and fee pubkeys for their placeholders.
         for acc in next_instruction.clone().accounts {
             let acc_pubkey = if acc.pubkey == PAYER_PUBKEY {
                 signatory_pubkey
+            } else if acc.pubkey == THREAD_PUBKEY {
+                thread_pubkey
+            } else if acc.pubkey == WORKER_PUBKEY {
+                worker_pubkey
+            } else if acc.pubkey == FEE_PUBKEY {
+                clockwork_network_program::state::Fee::pubkey(worker_pubkey)
+            } else if let Some(signer) =
+                thread_signer_index(&acc.pubkey).and_then(|index| signers.get(index as usize))
+            {
+                signer.pubkey(thread_pubkey)
             } else {
                 acc.pubkey
             };
@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use anchor_lang::{solana_program::instruction::Instruction, InstructionData, ToAccountMetas};
+use clockwork_network_program::state::{Fee, Pool, Worker};
+use clockwork_thread_program::state::{Thread, ThreadIntent};
+use clockwork_utils::thread::{
+    thread_signer_index, FEE_PUBKEY, PAYER_PUBKEY, THREAD_PUBKEY, WORKER_PUBKEY,
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::{instruction::AccountMeta, sysvar};
+use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+
+pub async fn build_thread_exec_intent_tx(
+    client: Arc<RpcClient>,
+    keypair: &Keypair,
+    thread: &Thread,
+    intent: ThreadIntent,
+    signature: [u8; 64],
+    worker_id: u64,
+) -> Option<Transaction> {
+    // Build the instruction verifying the authority's signature of the intent.
+    let verify_ix = intent.verify_instruction(thread.current_authority(), signature);
+
+    // Build the instruction to execute the intent.
+    let worker_pubkey = Worker::pubkey(worker_id);
+    let mut exec_ix = Instruction {
+        program_id: clockwork_thread_program::ID,
+        accounts: clockwork_thread_program::accounts::ThreadExecIntent {
+            instructions: sysvar::instructions::ID,
+            pool: Pool::pubkey(0),
+            signatory: keypair.pubkey(),
+            thread: intent.thread,
+            worker: worker_pubkey,
+        }
+        .to_account_metas(Some(false)),
+        data: clockwork_thread_program::instruction::ThreadExecIntent {
+            intent: intent.clone(),
+        }
+        .data(),
+    };
+
+    // Inject the target program account, and the intent's accounts with the payer, thread, worker, fee, and derived signer placeholders replaced.
+    exec_ix.accounts.push(AccountMeta::new_readonly(
+        intent.instruction.program_id,
+        false,
+    ));
+    for acc in intent.instruction.accounts {
+        let acc_pubkey = if acc.pubkey == PAYER_PUBKEY {
+            keypair.pubkey()
+        } else if acc.pubkey == THREAD_PUBKEY {
+            intent.thread
+        } else if acc.pubkey == WORKER_PUBKEY {
+            worker_pubkey
+        } else if acc.pubkey == FEE_PUBKEY {
+            Fee::pubkey(worker_pubkey)
+        } else if let Some(signer) =
+            thread_signer_index(&acc.pubkey).and_then(|index| thread.signers.get(index as usize))
+        {
+            signer.pubkey(intent.thread)
+        } else {
+            acc.pubkey
+        };
+        exec_ix.accounts.push(match acc.is_writable {
+            true => AccountMeta::new(acc_pubkey, false),
+            false => AccountMeta::new_readonly(acc_pubkey, false),
+        })
+    }
+
+    // Build and sign tx.
+    let blockhash = client.get_latest_blockhash().await.ok()?;
+    let mut tx = Transaction::new_with_payer(&[verify_ix, exec_ix], Some(&keypair.pubkey()));
+    tx.sign(&[keypair], blockhash);
+    Some(tx)
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use anchor_lang::{solana_program::instruction::Instruction, InstructionData, ToAccountMetas};
+use clockwork_network_program::state::{Pool, Worker};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+
+pub async fn build_thread_report_failure_tx(
+    client: Arc<RpcClient>,
+    keypair: &Keypair,
+    thread_pubkey: Pubkey,
+    worker_id: u64,
+    error_code: Option<u32>,
+) -> Option<Transaction> {
+    // Build the instruction to report the thread's failure.
+    let ix = Instruction {
+        program_id: clockwork_thread_program::ID,
+        accounts: clockwork_thread_program::accounts::ThreadReportFailure {
+            pool: Pool::pubkey(0),
+            signatory: keypair.pubkey(),
+            thread: thread_pubkey,
+            worker: Worker::pubkey(worker_id),
+        }
+        .to_account_metas(Some(false)),
+        data: clockwork_thread_program::instruction::ThreadReportFailure { error_code }.data(),
+    };
+
+    // Build and sign tx.
+    let blockhash = client.get_latest_blockhash().await.ok()?;
+    let mut tx = Transaction::new_with_payer(&[ix], Some(&keypair.pubkey()));
+    tx.sign(&[keypair], blockhash);
+    Some(tx)
+}
@@ -1,31 +1,71 @@
-use anchor_lang::{prelude::AccountInfo, AccountDeserialize, Discriminator};
+use std::str::FromStr;
+
+use anchor_lang::{AccountDeserialize, Discriminator};
 use bincode::deserialize;
-use clockwork_thread_program::state::{Thread as ThreadV2, VersionedThread};
+use clockwork_thread_program::state::{
+    OraclePrice, OracleSource, Thread as ThreadV2, VersionedThread,
+};
 use clockwork_thread_program_v1::state::Thread as ThreadV1;
 use clockwork_webhook_program::state::Webhook;
-use pyth_sdk_solana::{load_price_feed_from_account_info, PriceFeed};
 use solana_geyser_plugin_interface::geyser_plugin_interface::{
-    GeyserPluginError, ReplicaAccountInfo,
+    GeyserPluginError, ReplicaAccountInfo, Result as PluginResult,
 };
 use solana_program::{clock::Clock, pubkey::Pubkey, sysvar};
-use static_pubkey::static_pubkey;
 
-static PYTH_ORACLE_PROGRAM_ID_MAINNET: Pubkey = 
-    static_pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
-static PYTH_ORACLE_PROGRAM_ID_DEVNET: Pubkey =
-    static_pubkey!("gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s");
+use crate::config::OracleProgramIds;
 
 #[derive(Debug)]
 pub enum AccountUpdateEvent {
     Clock { clock: Clock },
     Thread { thread: VersionedThread },
-    PriceFeed { price_feed: PriceFeed },
+    PriceFeed { price: OraclePrice },
     Webhook { webhook: Webhook },
 }
 
-impl TryFrom<&mut ReplicaAccountInfo<'_>> for AccountUpdateEvent {
-    type Error = GeyserPluginError;
-    fn try_from(account_info: &mut ReplicaAccountInfo) -> Result<Self, Self::Error> {
+/// The oracle programs whose price feed accounts are parsed, by oracle source.
+#[derive(Debug)]
+pub struct OraclePrograms {
+    pub pyth: Vec<Pubkey>,
+    pub switchboard: Vec<Pubkey>,
+}
+
+impl OraclePrograms {
+    pub fn new(program_ids: &OracleProgramIds) -> PluginResult<Self> {
+        let parse = |program_ids: &Vec<String>| {
+            program_ids
+                .iter()
+                .map(|program_id| {
+                    Pubkey::from_str(program_id).map_err(|_| {
+                        GeyserPluginError::ConfigFileReadError {
+                            msg: format!("Invalid oracle program ID: {}", program_id),
+                        }
+                    })
+                })
+                .collect::<PluginResult<Vec<Pubkey>>>()
+        };
+        Ok(Self {
+            pyth: parse(&program_ids.pyth)?,
+            switchboard: parse(&program_ids.switchboard)?,
+        })
+    }
+
+    /// Returns the oracle source of the given account owner, if it is an oracle program.
+    fn source(&self, owner_pubkey: &Pubkey) -> Option<OracleSource> {
+        if self.pyth.contains(owner_pubkey) {
+            Some(OracleSource::Pyth)
+        } else if self.switchboard.contains(owner_pubkey) {
+            Some(OracleSource::Switchboard)
+        } else {
+            None
+        }
+    }
+}
+
+impl AccountUpdateEvent {
+    pub fn try_from_account(
+        account_info: &mut ReplicaAccountInfo,
+        oracle_programs: &OraclePrograms,
+    ) -> PluginResult<Self> {
         // Parse pubkeys.
         let account_pubkey = Pubkey::try_from(account_info.pubkey).unwrap();
         let owner_pubkey = Pubkey::try_from(account_info.owner).unwrap();
@@ -73,27 +113,14 @@ impl TryFrom<&mut ReplicaAccountInfo<'_>> for AccountUpdateEvent {
             }
         }
 
-        // If the account belongs to Pyth, attempt to parse it.
-        if owner_pubkey.eq(&PYTH_ORACLE_PROGRAM_ID_MAINNET)
-            || owner_pubkey.eq(&PYTH_ORACLE_PROGRAM_ID_DEVNET)
-        {
-            let data = &mut account_info.data.to_vec();
-            let acc_info = AccountInfo::new(
-                &account_pubkey,
-                false,
-                false,
-                &mut account_info.lamports,
-                data,
-                &owner_pubkey,
-                account_info.executable,
-                account_info.rent_epoch,
-            );
-            let price_feed = load_price_feed_from_account_info(&acc_info).map_err(|_| {
+        // If the account belongs to an oracle program, attempt to parse its price.
+        if let Some(source) = oracle_programs.source(&owner_pubkey) {
+            let price = source.load_price(account_info.data).ok_or_else(|| {
                 GeyserPluginError::AccountsUpdateError {
-                    msg: "Failed to parse Pyth price account".into(),
+                    msg: format!("Failed to parse {:?} price account", source),
                 }
             })?;
-            return Ok(AccountUpdateEvent::PriceFeed { price_feed });
+            return Ok(AccountUpdateEvent::PriceFeed { price });
         }
 
         // If the account belongs to the webhook program, parse in
@@ -5,11 +5,12 @@ use std::{
         atomic::{AtomicU64, Ordering},
         Arc,
     },
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use bincode::serialize;
 use clockwork_network_program::state::{Pool, Registry, Snapshot, SnapshotFrame, Worker};
-use clockwork_thread_program::state::VersionedThread;
+use clockwork_thread_program::state::{Thread, VersionedThread};
 use log::info;
 use solana_client::{
     nonblocking::{rpc_client::RpcClient, tpu_client::TpuClient},
@@ -24,11 +25,20 @@ use solana_quic_client::{QuicConfig, QuicConnectionManager, QuicPool};
 use solana_sdk::{
     commitment_config::CommitmentConfig,
     signature::{Keypair, Signature},
-    transaction::Transaction,
+    transaction::{Transaction, VersionedTransaction},
+};
+use tokio::{
+    runtime::Runtime,
+    sync::{Mutex, RwLock},
 };
-use tokio::{runtime::Runtime, sync::RwLock};
 
-use crate::{config::PluginConfig, pool_position::PoolPosition, utils::read_or_new_keypair};
+use crate::{
+    builders::ThreadExecTx,
+    config::PluginConfig,
+    intents::{is_stale, IntentQueue},
+    pool_position::PoolPosition,
+    utils::read_or_new_keypair,
+};
 
 use super::AccountGet;
 
@@ -54,12 +64,14 @@ pub struct TxExecutor {
     pub transaction_history: RwLock<HashMap<Pubkey, TransactionMetadata>>,
     pub rotation_history: RwLock<Option<TransactionMetadata>>,
     pub dropped_threads: AtomicU64,
+    pub intent_queue: Mutex<IntentQueue>,
     pub keypair: Keypair,
 }
 
 #[derive(Debug)]
 pub struct ExecutableThreadMetadata {
     pub due_slot: u64,
+    pub last_error_code: Option<u32>,
     pub simulation_failures: u32,
 }
 
@@ -78,6 +90,7 @@ impl TxExecutor {
             transaction_history: RwLock::new(HashMap::new()),
             rotation_history: RwLock::new(None),
             dropped_threads: AtomicU64::new(0),
+            intent_queue: Mutex::new(IntentQueue::new(config.intent_sources.clone())),
             keypair: read_or_new_keypair(config.keypath),
         }
     }
@@ -96,15 +109,18 @@ impl TxExecutor {
                 *pubkey,
                 ExecutableThreadMetadata {
                     due_slot: slot,
+                    last_error_code: None,
                     simulation_failures: 0,
                 },
             );
         });
 
         // Drop threads that cross the simulation failure threshold.
-        w_executable_threads.retain(|_thread_pubkey, metadata| {
+        let mut failed_threads: Vec<(Pubkey, Option<u32>)> = vec![];
+        w_executable_threads.retain(|thread_pubkey, metadata| {
             if metadata.simulation_failures > MAX_THREAD_SIMULATION_FAILURES {
                 self.dropped_threads.fetch_add(1, Ordering::Relaxed);
+                failed_threads.push((*thread_pubkey, metadata.last_error_code));
                 false
             } else {
                 true
@@ -146,6 +162,19 @@ impl TxExecutor {
                     .ok();
             }
 
+            // Report the failures of dropped threads, so they are visible on-chain.
+            // Then execute the pending intents signed by thread authorities.
+            if pool_position.current_position.is_some() {
+                self.clone()
+                    .execute_thread_report_failure_txs(client.clone(), failed_threads)
+                    .await
+                    .ok();
+                self.clone()
+                    .execute_thread_exec_intent_txs(client.clone(), slot)
+                    .await
+                    .ok();
+            }
+
             // Execute thread transactions.
             self.clone()
                 .execute_thread_exec_txs(client.clone(), slot, pool_position, runtime.clone())
@@ -231,6 +260,7 @@ impl TxExecutor {
                 pubkey,
                 ExecutableThreadMetadata {
                     due_slot,
+                    last_error_code: None,
                     simulation_failures: 0,
                 },
             );
@@ -417,16 +447,100 @@ impl TxExecutor {
         Ok(())
     }
 
+    async fn execute_thread_report_failure_txs(
+        self: Arc<Self>,
+        client: Arc<RpcClient>,
+        failed_threads: Vec<(Pubkey, Option<u32>)>,
+    ) -> PluginResult<()> {
+        for (thread_pubkey, error_code) in failed_threads {
+            if let Some(tx) = crate::builders::build_thread_report_failure_tx(
+                client.clone(),
+                &self.keypair,
+                thread_pubkey,
+                self.config.worker_id,
+                error_code,
+            )
+            .await
+            {
+                // The report may fail if the thread has since been paused or executed.
+                if self.clone().simulate_tx(&tx).await.is_ok() {
+                    self.clone().submit_tx(&tx).await.ok();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn execute_thread_exec_intent_txs(
+        self: Arc<Self>,
+        client: Arc<RpcClient>,
+        slot: u64,
+    ) -> PluginResult<()> {
+        // Skip this slot if the intents of a prior slot are still being processed.
+        let mut intent_queue = match self.intent_queue.try_lock() {
+            Err(_) => return Ok(()),
+            Ok(intent_queue) => intent_queue,
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        let mut completed_intents = vec![];
+        for (signed_intent, intent, signature) in intent_queue.pending_intents(slot).await {
+            let thread = match client.get::<Thread>(&intent.thread).await {
+                Err(_err) => {
+                    intent_queue.record_failure(&signed_intent, slot);
+                    continue;
+                }
+                Ok(thread) => thread,
+            };
+
+            // Drop intents which have expired or already been executed.
+            if is_stale(&intent, thread.intent_nonce, now) {
+                info!("Dropping stale intent: {:?}", signed_intent);
+                completed_intents.push(signed_intent);
+                continue;
+            }
+
+            match crate::builders::build_thread_exec_intent_tx(
+                client.clone(),
+                &self.keypair,
+                &thread,
+                intent,
+                signature,
+                self.config.worker_id,
+            )
+            .await
+            {
+                None => intent_queue.record_failure(&signed_intent, slot),
+                Some(tx) => match self.clone().simulate_tx(&tx).await {
+                    Ok(_) => {
+                        if self.clone().submit_tx(&tx).await.is_ok() {
+                            completed_intents.push(signed_intent);
+                        } else {
+                            intent_queue.record_failure(&signed_intent, slot);
+                        }
+                    }
+                    Err(err) => {
+                        info!("Intent failed simulation: {}", err);
+                        intent_queue.record_failure(&signed_intent, slot);
+                    }
+                },
+            }
+        }
+        intent_queue.remove(&completed_intents);
+        Ok(())
+    }
+
     pub async fn try_build_thread_exec_tx(
         self: Arc<Self>,
         client: Arc<RpcClient>,
         observed_slot: u64,
         due_slot: u64,
         thread_pubkey: Pubkey,
-    ) -> Option<(Pubkey, Transaction, u64)> {
+    ) -> Option<(Pubkey, VersionedTransaction, u64)> {
         let thread = match client.clone().get::<VersionedThread>(&thread_pubkey).await {
             Err(_err) => {
-                self.increment_simulation_failure(thread_pubkey).await;
+                self.increment_simulation_failure(thread_pubkey, None).await;
                 return None;
             }
             Ok(thread) => thread,
@@ -443,7 +557,7 @@ impl TxExecutor {
             }
         }
 
-        if let Ok(tx) = crate::builders::build_thread_exec_tx(
+        match crate::builders::build_thread_exec_tx(
             client.clone(),
             &self.keypair,
             due_slot,
@@ -453,7 +567,13 @@ impl TxExecutor {
         )
         .await
         {
-            if let Some(tx) = tx {
+            Err(_err) => None,
+            Ok(ThreadExecTx::Failed(error_code)) => {
+                self.increment_simulation_failure(thread_pubkey, error_code)
+                    .await;
+                None
+            }
+            Ok(ThreadExecTx::Executable(tx)) => {
                 if self
                     .clone()
                     .dedupe_tx(observed_slot, thread_pubkey, &tx)
@@ -464,20 +584,22 @@ impl TxExecutor {
                 } else {
                     None
                 }
-            } else {
-                self.increment_simulation_failure(thread_pubkey).await;
-                None
             }
-        } else {
-            None
         }
     }
 
-    pub async fn increment_simulation_failure(self: Arc<Self>, thread_pubkey: Pubkey) {
+    pub async fn increment_simulation_failure(
+        self: Arc<Self>,
+        thread_pubkey: Pubkey,
+        error_code: Option<u32>,
+    ) {
         let mut w_executable_threads = self.executable_threads.write().await;
         w_executable_threads
             .entry(thread_pubkey)
-            .and_modify(|metadata| metadata.simulation_failures += 1);
+            .and_modify(|metadata| {
+                metadata.last_error_code = error_code;
+                metadata.simulation_failures += 1;
+            });
         drop(w_executable_threads);
     }
 
@@ -485,7 +607,7 @@ impl TxExecutor {
         self: Arc<Self>,
         slot: u64,
         thread_pubkey: Pubkey,
-        tx: &Transaction,
+        tx: &VersionedTransaction,
     ) -> PluginResult<()> {
         let r_transaction_history = self.transaction_history.read().await;
         if let Some(metadata) = r_transaction_history.get(&thread_pubkey) {
@@ -0,0 +1,275 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::OpenOptions,
+    io::Write,
+    path::Path,
+};
+
+use anchor_lang::AnchorDeserialize;
+use clockwork_thread_program::state::ThreadIntent;
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::config::IntentSources;
+
+/// Number of slots to reuse the intents fetched from the endpoint before fetching them again.
+static ENDPOINT_FETCH_PERIOD: u64 = 10;
+
+/// The constant of the exponential backoff function.
+static EXPONENTIAL_BACKOFF_CONSTANT: u64 = 2;
+
+/// The maximum number of slots to back off for after repeated failures.
+static MAX_BACKOFF_SLOTS: u64 = 1024;
+
+/// A thread intent signed by the thread's authority, as read from an intent source.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SignedIntent {
+    /// The base58-encoded borsh serialization of the intent.
+    pub intent: String,
+    /// The base58-encoded ed25519 signature of the intent's message by the thread's authority.
+    pub signature: String,
+}
+
+impl SignedIntent {
+    /// Decodes the intent and its signature. Returns None if either is malformed.
+    pub fn decode(&self) -> Option<(ThreadIntent, [u8; 64])> {
+        let intent = bs58::decode(&self.intent).into_vec().ok()?;
+        let signature = bs58::decode(&self.signature).into_vec().ok()?;
+        Some((
+            ThreadIntent::try_from_slice(intent.as_slice()).ok()?,
+            signature.try_into().ok()?,
+        ))
+    }
+}
+
+/// Returns true if the intent can never be executed, because it has expired or its nonce has already been used.
+pub fn is_stale(intent: &ThreadIntent, thread_intent_nonce: u64, now: i64) -> bool {
+    now > intent.expires_at || intent.nonce <= thread_intent_nonce
+}
+
+/// Tracks the slot after which a failed operation may be retried.
+#[derive(Debug, Default)]
+struct Backoff {
+    failures: u32,
+    retry_at: u64,
+}
+
+impl Backoff {
+    fn is_ready(&self, slot: u64) -> bool {
+        slot >= self.retry_at
+    }
+
+    fn record_failure(&mut self, slot: u64, period: u64) {
+        self.failures = self.failures.saturating_add(1);
+        let delay = EXPONENTIAL_BACKOFF_CONSTANT
+            .saturating_pow(self.failures)
+            .saturating_mul(period)
+            .min(MAX_BACKOFF_SLOTS);
+        self.retry_at = slot.saturating_add(delay);
+    }
+}
+
+/// The intents read from the configured sources which have not yet been submitted.
+/// Queued intents are kept in a pending file next to the queue until they are submitted or become stale.
+#[derive(Debug, Default)]
+pub struct IntentQueue {
+    sources: IntentSources,
+    // The intents last fetched from the endpoint, and the slot they were fetched at.
+    endpoint_intents: Vec<SignedIntent>,
+    endpoint_fetched_at: Option<u64>,
+    endpoint_backoff: Backoff,
+    // Map from the encoded intents which failed simulation to their backoff.
+    simulation_backoffs: HashMap<String, Backoff>,
+}
+
+impl IntentQueue {
+    pub fn new(sources: IntentSources) -> Self {
+        Self {
+            sources,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the pending intents which are not backing off from a failed simulation.
+    /// Malformed intents are logged and dropped.
+    pub async fn pending_intents(
+        &mut self,
+        slot: u64,
+    ) -> Vec<(SignedIntent, ThreadIntent, [u8; 64])> {
+        let mut signed_intents = self.read_queue();
+
+        // Fetch the intents from the HTTP endpoint, reusing the last fetch until it is due to be refreshed.
+        if let Some(endpoint) = self.sources.endpoint.clone() {
+            let is_due = self.endpoint_fetched_at.map_or(true, |fetched_at| {
+                slot >= fetched_at + ENDPOINT_FETCH_PERIOD
+            });
+            if is_due && self.endpoint_backoff.is_ready(slot) {
+                match fetch_intents(&endpoint).await {
+                    Ok(fetched_intents) => {
+                        self.endpoint_intents = fetched_intents;
+                        self.endpoint_fetched_at = Some(slot);
+                        self.endpoint_backoff = Backoff::default();
+                    }
+                    Err(err) => {
+                        info!("Failed to fetch intents from {}: {}", endpoint, err);
+                        self.endpoint_backoff
+                            .record_failure(slot, ENDPOINT_FETCH_PERIOD);
+                    }
+                }
+            }
+            signed_intents.extend(self.endpoint_intents.iter().cloned());
+        }
+
+        // Forget the backoffs of intents which are no longer pending.
+        let pending: HashSet<&String> = signed_intents.iter().map(|s| &s.intent).collect();
+        self.simulation_backoffs
+            .retain(|intent, _backoff| pending.contains(intent));
+
+        let mut malformed_intents = vec![];
+        let decoded_intents = signed_intents
+            .into_iter()
+            .filter_map(|signed_intent| match signed_intent.decode() {
+                None => {
+                    info!("Failed to decode intent: {:?}", signed_intent);
+                    malformed_intents.push(signed_intent);
+                    None
+                }
+                Some((intent, signature)) => Some((signed_intent, intent, signature)),
+            })
+            .filter(|(signed_intent, _intent, _signature)| {
+                self.simulation_backoffs
+                    .get(&signed_intent.intent)
+                    .map_or(true, |backoff| backoff.is_ready(slot))
+            })
+            .collect();
+        self.remove(&malformed_intents);
+        decoded_intents
+    }
+
+    /// Records a failed simulation of the intent, so it is retried with exponential backoff.
+    pub fn record_failure(&mut self, signed_intent: &SignedIntent, slot: u64) {
+        self.simulation_backoffs
+            .entry(signed_intent.intent.clone())
+            .or_default()
+            .record_failure(slot, 1);
+    }
+
+    /// Removes intents which have been submitted, or which can never be executed.
+    pub fn remove(&mut self, signed_intents: &[SignedIntent]) {
+        if signed_intents.is_empty() {
+            return;
+        }
+        for signed_intent in signed_intents {
+            self.simulation_backoffs.remove(&signed_intent.intent);
+        }
+        self.endpoint_intents
+            .retain(|signed_intent| !signed_intents.contains(signed_intent));
+        if self.sources.queue_path.is_some() {
+            let retained_intents: Vec<SignedIntent> = self
+                .read_pending()
+                .into_iter()
+                .filter(|signed_intent| !signed_intents.contains(signed_intent))
+                .collect();
+            self.write_pending(&retained_intents);
+        }
+    }
+
+    /// Moves newly queued intents onto the end of the pending file, and returns all the pending intents.
+    /// The queue is moved aside before it is read, so intents appended meanwhile are kept for the next read.
+    fn read_queue(&self) -> Vec<SignedIntent> {
+        let queue_path = match &self.sources.queue_path {
+            None => return vec![],
+            Some(queue_path) => queue_path,
+        };
+        let incoming_path = format!("{}.incoming", queue_path);
+
+        // If a prior read failed to move the incoming intents into the pending file, retry it before moving the queue aside again.
+        if Path::new(&incoming_path).exists() || std::fs::rename(queue_path, &incoming_path).is_ok()
+        {
+            match self.append_pending(&incoming_path) {
+                Ok(()) => {
+                    std::fs::remove_file(&incoming_path).ok();
+                }
+                Err(err) => info!("Failed to move queued intents to the pending file: {}", err),
+            }
+        }
+        self.read_pending()
+    }
+
+    fn pending_path(&self) -> Option<String> {
+        self.sources
+            .queue_path
+            .as_ref()
+            .map(|queue_path| format!("{}.pending", queue_path))
+    }
+
+    fn append_pending(&self, incoming_path: &str) -> std::io::Result<()> {
+        let mut incoming = std::fs::read_to_string(incoming_path)?;
+        if !incoming.is_empty() && !incoming.ends_with('\n') {
+            incoming.push('\n');
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.pending_path().unwrap())?
+            .write_all(incoming.as_bytes())
+    }
+
+    /// Reads the pending intents. Lines which cannot be parsed are logged and dropped.
+    fn read_pending(&self) -> Vec<SignedIntent> {
+        let pending = match self
+            .pending_path()
+            .and_then(|pending_path| std::fs::read_to_string(pending_path).ok())
+        {
+            None => return vec![],
+            Some(pending) => pending,
+        };
+        let mut is_malformed = false;
+        let signed_intents: Vec<SignedIntent> = pending
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(signed_intent) => Some(signed_intent),
+                Err(err) => {
+                    info!("Failed to parse queued intent: {}", err);
+                    is_malformed = true;
+                    None
+                }
+            })
+            .collect();
+        if is_malformed {
+            self.write_pending(&signed_intents);
+        }
+        signed_intents
+    }
+
+    /// Replaces the pending file with the given intents. It is written aside and renamed into place, so it is never left partially written.
+    fn write_pending(&self, signed_intents: &[SignedIntent]) {
+        let pending_path = match self.pending_path() {
+            None => return,
+            Some(pending_path) => pending_path,
+        };
+        if signed_intents.is_empty() {
+            std::fs::remove_file(&pending_path).ok();
+            return;
+        }
+        let writing_path = format!("{}.writing", pending_path);
+        let pending: String = signed_intents
+            .iter()
+            .filter_map(|signed_intent| serde_json::to_string(signed_intent).ok())
+            .map(|line| line + "\n")
+            .collect();
+        if let Err(err) = std::fs::write(&writing_path, pending)
+            .and_then(|_| std::fs::rename(&writing_path, &pending_path))
+        {
+            info!("Failed to write the pending intents: {}", err);
+        }
+    }
+}
+
+async fn fetch_intents(endpoint: &str) -> reqwest::Result<Vec<SignedIntent>> {
+    reqwest::get(endpoint)
+        .await?
+        .json::<Vec<SignedIntent>>()
+        .await
+}
@@ -1,5 +1,5 @@
 pub mod config {
-    pub use clockwork_plugin_utils::PluginConfig;
+    pub use clockwork_plugin_utils::{IntentSources, OracleProgramIds, PluginConfig};
 }
 
 use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
@@ -10,6 +10,8 @@ mod events;
 
 mod executors;
 
+mod intents;
+
 mod observers;
 
 mod plugin;
@@ -3,13 +3,16 @@ use std::{
     fmt::Debug,
     str::FromStr,
     sync::{atomic::AtomicU64, Arc},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-use chrono::{DateTime, NaiveDateTime, Utc};
-use clockwork_cron::Schedule;
-use clockwork_thread_program::state::{Equality, Trigger, TriggerContext, VersionedThread};
+use chrono::TimeZone;
+use clockwork_cron::{Schedule, Tz};
+use clockwork_thread_program::state::{
+    is_price_confident, read_token_amount, DataValue, Equality, OraclePrice, PythMode, Trigger,
+    TriggerContext, TriggerContexts, VersionedThread, DEFAULT_PYTH_STALENESS,
+};
 use log::info;
-use pyth_sdk_solana::PriceFeed;
 use solana_geyser_plugin_interface::geyser_plugin_interface::{
     GeyserPluginError, Result as PluginResult,
 };
@@ -40,18 +43,58 @@ pub struct ThreadObserver {
     // The set of threads with an epoch trigger.
     pub epoch_threads: RwLock<HashMap<u64, HashSet<Pubkey>>>,
 
-    // The set of threads with a pyth trigger.
-    pub pyth_threads: RwLock<HashMap<Pubkey, HashSet<PythThread>>>,
+    // The set of threads with a pyth or oracle trigger.
+    // Map from price feed pubkeys to the threads listening to that feed, keyed by thread pubkey.
+    pub price_feed_threads: RwLock<HashMap<Pubkey, HashMap<Pubkey, PriceFeedThread>>>,
+
+    // The set of threads with an account value trigger.
+    // Map from account pubkeys to the threads monitoring a value in that account's data, keyed by thread pubkey.
+    pub account_value_threads: RwLock<HashMap<Pubkey, HashMap<Pubkey, AccountValueThread>>>,
+
+    // The set of threads with a token balance trigger.
+    // Map from token account pubkeys to the threads monitoring that account's balance, keyed by thread pubkey.
+    pub token_balance_threads: RwLock<HashMap<Pubkey, HashMap<Pubkey, TokenBalanceThread>>>,
+
+    // The set of threads with a thread completed trigger.
+    // Map from upstream thread pubkeys to the set of threads listening for that thread to complete a run.
+    pub thread_completed_threads: RwLock<HashMap<Pubkey, HashSet<Pubkey>>>,
+
+    // The number of runs each observed thread has completed, used to detect when it completes a new run.
+    // Map from thread pubkeys to their completed run counts.
+    pub thread_runs_completed: RwLock<HashMap<Pubkey, u64>>,
 
     // The set of accounts that have updated.
     pub updated_accounts: RwLock<HashSet<Pubkey>>,
 }
 
 #[derive(Eq, Hash, PartialEq)]
-pub struct PythThread {
+pub struct PriceFeedThread {
     pub thread_pubkey: Pubkey,
+    // The number of decimals to scale prices to. If none, the feed's own scale is used.
+    pub decimals: Option<u32>,
     pub equality: Equality,
     pub limit: i64,
+    pub mode: PythMode,
+    pub max_staleness: u64,
+    pub max_confidence_bps: Option<u64>,
+    pub prior_price: Option<i64>,
+}
+
+#[derive(Eq, Hash, PartialEq)]
+pub struct AccountValueThread {
+    pub thread_pubkey: Pubkey,
+    pub offset: u64,
+    pub equality: Equality,
+    pub threshold: DataValue,
+    pub prior_value: Option<DataValue>,
+}
+
+#[derive(Eq, Hash, PartialEq)]
+pub struct TokenBalanceThread {
+    pub thread_pubkey: Pubkey,
+    pub equality: Equality,
+    pub amount: u64,
+    pub prior_amount: Option<u64>,
 }
 
 impl ThreadObserver {
@@ -64,7 +107,11 @@ impl ThreadObserver {
             now_threads: RwLock::new(HashSet::new()),
             slot_threads: RwLock::new(HashMap::new()),
             epoch_threads: RwLock::new(HashMap::new()),
-            pyth_threads: RwLock::new(HashMap::new()),
+            price_feed_threads: RwLock::new(HashMap::new()),
+            account_value_threads: RwLock::new(HashMap::new()),
+            token_balance_threads: RwLock::new(HashMap::new()),
+            thread_completed_threads: RwLock::new(HashMap::new()),
+            thread_runs_completed: RwLock::new(HashMap::new()),
             updated_accounts: RwLock::new(HashSet::new()),
         }
     }
@@ -156,10 +203,26 @@ impl ThreadObserver {
         Ok(())
     }
 
+    /// Returns true if any thread is monitoring a value or token balance in this account's data.
+    /// If an index is currently locked, conservatively assume the account is monitored.
+    pub fn observes_account_data(&self, account_pubkey: &Pubkey) -> bool {
+        let observes_value = match self.account_value_threads.try_read() {
+            Ok(r_account_value_threads) => r_account_value_threads.contains_key(account_pubkey),
+            Err(_) => true,
+        };
+        observes_value
+            || match self.token_balance_threads.try_read() {
+                Ok(r_token_balance_threads) => r_token_balance_threads.contains_key(account_pubkey),
+                Err(_) => true,
+            }
+    }
+
     /// Move all threads listening to this account into the executable set.
+    /// Threads monitoring a value or token balance in the account's data are only moved if their condition is met.
     pub async fn observe_account(
         self: Arc<Self>,
         account_pubkey: Pubkey,
+        account_data: Option<Vec<u8>>,
         _slot: u64,
     ) -> PluginResult<()> {
         let r_account_threads = self.account_threads.read().await;
@@ -169,44 +232,116 @@ impl ThreadObserver {
             drop(w_updated_accounts);
         }
         drop(r_account_threads);
+
+        if let Some(account_data) = account_data {
+            let r_account_value_threads = self.account_value_threads.read().await;
+            if let Some(account_value_threads) = r_account_value_threads.get(&account_pubkey) {
+                for account_value_thread in account_value_threads.values() {
+                    let value = match account_value_thread
+                        .threshold
+                        .read(&account_data, account_value_thread.offset)
+                    {
+                        None => continue,
+                        Some(value) => value,
+                    };
+                    let threshold = &account_value_thread.threshold;
+                    let prior_value = account_value_thread.prior_value.as_ref();
+                    if account_value_thread
+                        .equality
+                        .is_satisfied(&value, threshold, prior_value)
+                        || account_value_thread
+                            .equality
+                            .is_rearmed(&value, threshold, prior_value)
+                    {
+                        let mut w_now_threads = self.now_threads.write().await;
+                        w_now_threads.insert(account_value_thread.thread_pubkey);
+                        drop(w_now_threads);
+                    }
+                }
+            }
+            drop(r_account_value_threads);
+
+            let r_token_balance_threads = self.token_balance_threads.read().await;
+            if let Some(token_balance_threads) = r_token_balance_threads.get(&account_pubkey) {
+                if let Some(amount) = read_token_amount(&account_data) {
+                    for token_balance_thread in token_balance_threads.values() {
+                        let threshold = &token_balance_thread.amount;
+                        let prior_amount = token_balance_thread.prior_amount.as_ref();
+                        if token_balance_thread.equality.is_satisfied(
+                            &amount,
+                            threshold,
+                            prior_amount,
+                        ) || token_balance_thread.equality.is_rearmed(
+                            &amount,
+                            threshold,
+                            prior_amount,
+                        ) {
+                            let mut w_now_threads = self.now_threads.write().await;
+                            w_now_threads.insert(token_balance_thread.thread_pubkey);
+                            drop(w_now_threads);
+                        }
+                    }
+                }
+            }
+            drop(r_token_balance_threads);
+        }
         Ok(())
     }
 
     pub async fn observe_price_feed(
         self: Arc<Self>,
         account_pubkey: Pubkey,
-        price_feed: PriceFeed,
+        price: OraclePrice,
     ) -> PluginResult<()> {
-        let r_pyth_threads = self.pyth_threads.read().await;
-        if let Some(pyth_threads) = r_pyth_threads.get(&account_pubkey) {
-            for pyth_thread in pyth_threads {
-                match pyth_thread.equality {
-                    Equality::GreaterThanOrEqual => {
-                        if price_feed
-                            .get_price_unchecked()
-                            .price
-                            .ge(&pyth_thread.limit)
-                        {
-                            let mut w_now_threads = self.now_threads.write().await;
-                            w_now_threads.insert(pyth_thread.thread_pubkey);
-                            drop(w_now_threads);
-                        }
-                    }
-                    Equality::LessThanOrEqual => {
-                        if price_feed
-                            .get_price_unchecked()
-                            .price
-                            .le(&pyth_thread.limit)
-                        {
-                            let mut w_now_threads = self.now_threads.write().await;
-                            w_now_threads.insert(pyth_thread.thread_pubkey);
-                            drop(w_now_threads);
-                        }
+        let r_price_feed_threads = self.price_feed_threads.read().await;
+        if let Some(price_feed_threads) = r_price_feed_threads.get(&account_pubkey) {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs() as i64);
+            for price_feed_thread in price_feed_threads.values() {
+                // Apply the same staleness and confidence checks as the thread program.
+                if !price.is_fresh(now, price_feed_thread.max_staleness) {
+                    continue;
+                }
+                let (current_price, conf) =
+                    match price.scaled(price_feed_thread.decimals.unwrap_or(price.scale)) {
+                        None => continue,
+                        Some(scaled) => scaled,
+                    };
+                if !is_price_confident(current_price, conf, price_feed_thread.max_confidence_bps) {
+                    continue;
+                }
+
+                // In relative mode, a thread with no baseline price is kicked off to record one.
+                let should_kickoff = match price_feed_thread
+                    .mode
+                    .value(current_price, price_feed_thread.prior_price)
+                {
+                    None => true,
+                    Some(value) => {
+                        let prior_value = match price_feed_thread.mode {
+                            PythMode::Absolute => price_feed_thread.prior_price,
+                            PythMode::Relative => None,
+                        };
+                        price_feed_thread.equality.is_satisfied(
+                            &value,
+                            &price_feed_thread.limit,
+                            prior_value.as_ref(),
+                        ) || price_feed_thread.equality.is_rearmed(
+                            &value,
+                            &price_feed_thread.limit,
+                            prior_value.as_ref(),
+                        )
                     }
+                };
+                if should_kickoff {
+                    let mut w_now_threads = self.now_threads.write().await;
+                    w_now_threads.insert(price_feed_thread.thread_pubkey);
+                    drop(w_now_threads);
                 }
             }
         }
-        drop(r_pyth_threads);
+        drop(r_price_feed_threads);
         Ok(())
     }
 
@@ -216,6 +351,22 @@ impl ThreadObserver {
         thread_pubkey: Pubkey,
         slot: u64,
     ) -> PluginResult<()> {
+        // If the thread has completed a new run, move all threads listening for its completion into the executable set.
+        // Other updates to the thread (e.g. pausing or withdrawing from it) are not completions.
+        let runs_completed = thread.exec_counters().runs_completed;
+        let mut w_thread_runs_completed = self.thread_runs_completed.write().await;
+        let prior_runs_completed = w_thread_runs_completed.insert(thread_pubkey, runs_completed);
+        drop(w_thread_runs_completed);
+        if prior_runs_completed.map_or(false, |prior| runs_completed.gt(&prior)) {
+            let r_thread_completed_threads = self.thread_completed_threads.read().await;
+            if let Some(thread_pubkeys) = r_thread_completed_threads.get(&thread_pubkey) {
+                let mut w_now_threads = self.now_threads.write().await;
+                w_now_threads.extend(thread_pubkeys);
+                drop(w_now_threads);
+            }
+            drop(r_thread_completed_threads);
+        }
+
         // If the thread is paused, just return without indexing
         if thread.paused() {
             return Ok(());
@@ -229,144 +380,362 @@ impl ThreadObserver {
             drop(w_now_threads);
         } else {
             // Otherwise, index the thread according to its trigger type.
-            match thread.trigger() {
-                Trigger::Account {
-                    address,
-                    offset: _,
-                    size: _,
-                } => {
-                    // Index the thread by its trigger's account pubkey.
-                    let mut w_account_threads = self.account_threads.write().await;
-                    w_account_threads
-                        .entry(address)
-                        .and_modify(|v| {
-                            v.insert(thread_pubkey);
-                        })
-                        .or_insert_with(|| {
-                            let mut v = HashSet::new();
-                            v.insert(thread_pubkey);
-                            v
-                        });
-                    drop(w_account_threads);
-
-                    // Threads with account triggers might be immediately executable,
-                    // Thus, we should attempt to execute these threads right away without for an account update.
-                    let mut w_now_threads = self.now_threads.write().await;
-                    w_now_threads.insert(thread_pubkey);
-                    drop(w_now_threads);
-                }
-                Trigger::Cron {
-                    schedule,
-                    skippable: _,
-                } => {
-                    // Find a reference timestamp for calculating the thread's upcoming target time.
-                    let reference_timestamp = match thread.exec_context() {
-                        None => thread.created_at().unix_timestamp,
-                        Some(exec_context) => match exec_context.trigger_context {
-                            TriggerContext::Cron { started_at } => started_at,
-                            _ => {
-                                return Err(GeyserPluginError::Custom(
-                                    "Invalid exec context".into(),
-                                ))
-                            }
-                        },
-                    };
+            // Composite triggers are indexed under each of their child triggers.
+            for (trigger, trigger_context) in leaf_triggers(
+                thread.trigger(),
+                thread.exec_context().map(|e| e.trigger_context),
+            ) {
+                match trigger {
+                    Trigger::Account { address, .. }
+                    | Trigger::AccountComponents { address, .. } => {
+                        // Index the thread by its trigger's account pubkey.
+                        let mut w_account_threads = self.account_threads.write().await;
+                        w_account_threads
+                            .entry(address)
+                            .and_modify(|v| {
+                                v.insert(thread_pubkey);
+                            })
+                            .or_insert_with(|| {
+                                let mut v = HashSet::new();
+                                v.insert(thread_pubkey);
+                                v
+                            });
+                        drop(w_account_threads);
 
-                    // Index the thread to its target timestamp
-                    match next_moment(reference_timestamp, schedule) {
-                        None => {} // The thread does not have any upcoming scheduled target time
-                        Some(target_timestamp) => {
-                            let mut w_cron_threads = self.cron_threads.write().await;
-                            w_cron_threads
-                                .entry(target_timestamp)
-                                .and_modify(|v| {
-                                    v.insert(thread_pubkey);
-                                })
-                                .or_insert_with(|| {
-                                    let mut v = HashSet::new();
-                                    v.insert(thread_pubkey);
-                                    v
-                                });
-                            drop(w_cron_threads);
+                        // Threads with account triggers might be immediately executable,
+                        // Thus, we should attempt to execute these threads right away without for an account update.
+                        let mut w_now_threads = self.now_threads.write().await;
+                        w_now_threads.insert(thread_pubkey);
+                        drop(w_now_threads);
+                    }
+                    Trigger::Cron { schedule, .. } => {
+                        self.index_cron_thread(
+                            &thread,
+                            thread_pubkey,
+                            trigger_context,
+                            schedule,
+                            None,
+                        )
+                        .await?;
+                    }
+                    Trigger::CronTz {
+                        schedule, timezone, ..
+                    } => {
+                        self.index_cron_thread(
+                            &thread,
+                            thread_pubkey,
+                            trigger_context,
+                            schedule,
+                            Some(timezone),
+                        )
+                        .await?;
+                    }
+                    Trigger::Timestamp { unix_ts } => {
+                        let mut w_cron_threads = self.cron_threads.write().await;
+                        w_cron_threads
+                            .entry(unix_ts)
+                            .and_modify(|v| {
+                                v.insert(thread_pubkey);
+                            })
+                            .or_insert_with(|| {
+                                let mut v = HashSet::new();
+                                v.insert(thread_pubkey);
+                                v
+                            });
+                        drop(w_cron_threads);
+                    }
+                    Trigger::Now => {
+                        let mut w_now_threads = self.now_threads.write().await;
+                        w_now_threads.insert(thread_pubkey);
+                        drop(w_now_threads);
+                    }
+                    Trigger::Signal { .. } => {
+                        // Signaled threads are armed until their next kickoff.
+                        if let Some(TriggerContext::Signal { armed: true, .. }) = trigger_context {
+                            let mut w_now_threads = self.now_threads.write().await;
+                            w_now_threads.insert(thread_pubkey);
+                            drop(w_now_threads);
                         }
                     }
-                }
-                Trigger::Timestamp { unix_ts } => {
-                    let mut w_cron_threads = self.cron_threads.write().await;
-                    w_cron_threads
-                        .entry(unix_ts)
-                        .and_modify(|v| {
-                            v.insert(thread_pubkey);
-                        })
-                        .or_insert_with(|| {
-                            let mut v = HashSet::new();
-                            v.insert(thread_pubkey);
-                            v
-                        });
-                    drop(w_cron_threads);
-                }
-                Trigger::Now => {
-                    let mut w_now_threads = self.now_threads.write().await;
-                    w_now_threads.insert(thread_pubkey);
-                    drop(w_now_threads);
-                }
-                Trigger::Slot { slot } => {
-                    let mut w_slot_threads = self.slot_threads.write().await;
-                    w_slot_threads
-                        .entry(slot)
-                        .and_modify(|v| {
-                            v.insert(thread_pubkey);
-                        })
-                        .or_insert_with(|| {
-                            let mut v = HashSet::new();
-                            v.insert(thread_pubkey);
-                            v
-                        });
-                    drop(w_slot_threads);
-                }
-                Trigger::Epoch { epoch } => {
-                    let mut w_epoch_threads = self.epoch_threads.write().await;
-                    w_epoch_threads
-                        .entry(epoch)
-                        .and_modify(|v| {
-                            v.insert(thread_pubkey);
-                        })
-                        .or_insert_with(|| {
-                            let mut v = HashSet::new();
-                            v.insert(thread_pubkey);
-                            v
-                        });
-                    drop(w_epoch_threads);
-                }
-                Trigger::Pyth {
-                    price_feed,
-                    equality,
-                    limit,
-                } => {
-                    let mut w_pyth_threads = self.pyth_threads.write().await;
-                    w_pyth_threads
-                        .entry(price_feed)
-                        .and_modify(|v| {
-                            v.insert(PythThread {
+                    Trigger::Slot { slot } => {
+                        let mut w_slot_threads = self.slot_threads.write().await;
+                        w_slot_threads
+                            .entry(slot)
+                            .and_modify(|v| {
+                                v.insert(thread_pubkey);
+                            })
+                            .or_insert_with(|| {
+                                let mut v = HashSet::new();
+                                v.insert(thread_pubkey);
+                                v
+                            });
+                        drop(w_slot_threads);
+                    }
+                    Trigger::Epoch { epoch } => {
+                        let mut w_epoch_threads = self.epoch_threads.write().await;
+                        w_epoch_threads
+                            .entry(epoch)
+                            .and_modify(|v| {
+                                v.insert(thread_pubkey);
+                            })
+                            .or_insert_with(|| {
+                                let mut v = HashSet::new();
+                                v.insert(thread_pubkey);
+                                v
+                            });
+                        drop(w_epoch_threads);
+                    }
+                    Trigger::Pyth {
+                        price_feed,
+                        equality,
+                        limit,
+                    } => {
+                        let prior_price = match trigger_context {
+                            Some(TriggerContext::Pyth { price }) => Some(price),
+                            _ => None,
+                        };
+                        let mut w_price_feed_threads = self.price_feed_threads.write().await;
+                        w_price_feed_threads.entry(price_feed).or_default().insert(
+                            thread_pubkey,
+                            PriceFeedThread {
+                                thread_pubkey,
+                                decimals: None,
+                                equality,
+                                limit,
+                                // Pyth triggers compare the price itself, with the default staleness threshold.
+                                mode: PythMode::Absolute,
+                                max_staleness: DEFAULT_PYTH_STALENESS,
+                                max_confidence_bps: None,
+                                prior_price,
+                            },
+                        );
+                        drop(w_price_feed_threads);
+                    }
+                    Trigger::PythPrice {
+                        price_feed,
+                        equality,
+                        limit,
+                        mode,
+                        max_staleness,
+                        max_confidence_bps,
+                    } => {
+                        let prior_price = match trigger_context {
+                            Some(TriggerContext::Pyth { price }) => Some(price),
+                            _ => None,
+                        };
+                        let mut w_price_feed_threads = self.price_feed_threads.write().await;
+                        w_price_feed_threads.entry(price_feed).or_default().insert(
+                            thread_pubkey,
+                            PriceFeedThread {
                                 thread_pubkey,
-                                equality: equality.clone(),
+                                decimals: None,
+                                equality,
                                 limit,
-                            });
-                        })
-                        .or_insert_with(|| {
-                            let mut v = HashSet::new();
-                            v.insert(PythThread {
+                                mode,
+                                max_staleness,
+                                max_confidence_bps,
+                                prior_price,
+                            },
+                        );
+                        drop(w_price_feed_threads);
+                    }
+                    Trigger::Oracle {
+                        source: _,
+                        price_feed,
+                        decimals,
+                        equality,
+                        limit,
+                        mode,
+                        max_staleness,
+                        max_confidence_bps,
+                    } => {
+                        let prior_price = match trigger_context {
+                            Some(TriggerContext::Oracle { price }) => Some(price),
+                            _ => None,
+                        };
+                        let mut w_price_feed_threads = self.price_feed_threads.write().await;
+                        w_price_feed_threads.entry(price_feed).or_default().insert(
+                            thread_pubkey,
+                            PriceFeedThread {
                                 thread_pubkey,
+                                decimals: Some(decimals),
                                 equality,
                                 limit,
-                            });
-                            v
-                        });
-                    drop(w_pyth_threads);
+                                mode,
+                                max_staleness,
+                                max_confidence_bps,
+                                prior_price,
+                            },
+                        );
+                        drop(w_price_feed_threads);
+                    }
+                    Trigger::AccountValue {
+                        address,
+                        offset,
+                        equality,
+                        threshold,
+                    } => {
+                        // Index the thread by its trigger's account pubkey, along with the last recorded value.
+                        let prior_value = match trigger_context {
+                            Some(TriggerContext::AccountValue { value }) => Some(value),
+                            _ => None,
+                        };
+                        let mut w_account_value_threads = self.account_value_threads.write().await;
+                        w_account_value_threads.entry(address).or_default().insert(
+                            thread_pubkey,
+                            AccountValueThread {
+                                thread_pubkey,
+                                offset,
+                                equality,
+                                threshold,
+                                prior_value,
+                            },
+                        );
+                        drop(w_account_value_threads);
+
+                        // The account value might already satisfy the condition,
+                        // so attempt to execute the thread right away without waiting for an account update.
+                        let mut w_now_threads = self.now_threads.write().await;
+                        w_now_threads.insert(thread_pubkey);
+                        drop(w_now_threads);
+                    }
+                    Trigger::SlotInterval { start, interval } => {
+                        // Index the thread to the boundary following the last one it was kicked off at.
+                        let target_slot = match trigger_context {
+                            None => Some(start),
+                            Some(TriggerContext::SlotInterval { started_at }) => {
+                                started_at.checked_add(interval)
+                            }
+                            Some(_) => {
+                                return Err(GeyserPluginError::Custom(
+                                    "Invalid exec context".into(),
+                                ))
+                            }
+                        };
+                        if let Some(target_slot) = target_slot {
+                            let mut w_slot_threads = self.slot_threads.write().await;
+                            w_slot_threads
+                                .entry(target_slot)
+                                .or_default()
+                                .insert(thread_pubkey);
+                            drop(w_slot_threads);
+                        }
+                    }
+                    Trigger::EpochInterval { interval, offset } => {
+                        // Index the thread to the boundary following the last one it was kicked off at.
+                        let target_epoch = match trigger_context {
+                            None => Some(offset),
+                            Some(TriggerContext::EpochInterval { started_at }) => {
+                                started_at.checked_add(interval)
+                            }
+                            Some(_) => {
+                                return Err(GeyserPluginError::Custom(
+                                    "Invalid exec context".into(),
+                                ))
+                            }
+                        };
+                        if let Some(target_epoch) = target_epoch {
+                            let mut w_epoch_threads = self.epoch_threads.write().await;
+                            w_epoch_threads
+                                .entry(target_epoch)
+                                .or_default()
+                                .insert(thread_pubkey);
+                            drop(w_epoch_threads);
+                        }
+                    }
+                    Trigger::ThreadCompleted { thread } => {
+                        // Index the thread by the upstream thread's pubkey.
+                        let mut w_thread_completed_threads =
+                            self.thread_completed_threads.write().await;
+                        w_thread_completed_threads
+                            .entry(thread)
+                            .or_default()
+                            .insert(thread_pubkey);
+                        drop(w_thread_completed_threads);
+
+                        // The thread might not have recorded a baseline of the upstream thread's completed runs yet,
+                        // or the upstream thread might have completed a run since, so attempt to execute the thread right away.
+                        let mut w_now_threads = self.now_threads.write().await;
+                        w_now_threads.insert(thread_pubkey);
+                        drop(w_now_threads);
+                    }
+                    Trigger::TokenBalance {
+                        token_account,
+                        equality,
+                        amount,
+                    } => {
+                        // Index the thread by its token account pubkey, along with the last recorded balance.
+                        let prior_amount = match trigger_context {
+                            Some(TriggerContext::TokenBalance { amount }) => Some(amount),
+                            _ => None,
+                        };
+                        let mut w_token_balance_threads = self.token_balance_threads.write().await;
+                        w_token_balance_threads
+                            .entry(token_account)
+                            .or_default()
+                            .insert(
+                                thread_pubkey,
+                                TokenBalanceThread {
+                                    thread_pubkey,
+                                    equality,
+                                    amount,
+                                    prior_amount,
+                                },
+                            );
+                        drop(w_token_balance_threads);
+
+                        // The balance might already satisfy the condition,
+                        // so attempt to execute the thread right away without waiting for an account update.
+                        let mut w_now_threads = self.now_threads.write().await;
+                        w_now_threads.insert(thread_pubkey);
+                        drop(w_now_threads);
+                    }
+                    Trigger::Any { .. } | Trigger::All { .. } => {
+                        // Composite triggers are flattened into their child triggers above.
+                    }
                 }
             }
         }
 
+        Ok(())
+    }
+    /// Indexes a thread with a cron trigger to the upcoming moment of its schedule.
+    async fn index_cron_thread(
+        &self,
+        thread: &VersionedThread,
+        thread_pubkey: Pubkey,
+        trigger_context: Option<TriggerContext>,
+        schedule: String,
+        timezone: Option<String>,
+    ) -> PluginResult<()> {
+        // Find a reference timestamp for calculating the thread's upcoming target time.
+        let reference_timestamp = match trigger_context {
+            None => thread.created_at().unix_timestamp,
+            Some(trigger_context) => match trigger_context {
+                TriggerContext::Cron { started_at } => started_at,
+                _ => return Err(GeyserPluginError::Custom("Invalid exec context".into())),
+            },
+        };
+
+        // Index the thread to its target timestamp
+        match next_moment(reference_timestamp, schedule, timezone) {
+            None => {} // The thread does not have any upcoming scheduled target time
+            Some(target_timestamp) => {
+                let mut w_cron_threads = self.cron_threads.write().await;
+                w_cron_threads
+                    .entry(target_timestamp)
+                    .and_modify(|v| {
+                        v.insert(thread_pubkey);
+                    })
+                    .or_insert_with(|| {
+                        let mut v = HashSet::new();
+                        v.insert(thread_pubkey);
+                        v
+                    });
+                drop(w_cron_threads);
+            }
+        }
+
         Ok(())
     }
 }
@@ -377,14 +746,40 @@ impl Debug for ThreadObserver {
     }
 }
 
-fn next_moment(after: i64, schedule: String) -> Option<i64> {
+/// Flattens a trigger into its leaf (non-composite) triggers, each paired with its trigger context.
+fn leaf_triggers(
+    trigger: Trigger,
+    trigger_context: Option<TriggerContext>,
+) -> Vec<(Trigger, Option<TriggerContext>)> {
+    match trigger {
+        Trigger::Any { triggers } | Trigger::All { triggers } => {
+            let contexts = match trigger_context {
+                Some(TriggerContext::Any { contexts }) | Some(TriggerContext::All { contexts }) => {
+                    contexts
+                }
+                _ => TriggerContexts::default(),
+            };
+            triggers
+                .iter()
+                .enumerate()
+                .flat_map(|(i, trigger)| {
+                    leaf_triggers(trigger.clone(), contexts.get(i).cloned().flatten())
+                })
+                .collect()
+        }
+        _ => vec![(trigger, trigger_context)],
+    }
+}
+
+fn next_moment(after: i64, schedule: String, timezone: Option<String>) -> Option<i64> {
+    let timezone = match timezone {
+        None => Tz::UTC,
+        Some(timezone) => Tz::from_str(&timezone).ok()?,
+    };
     match Schedule::from_str(&schedule) {
         Err(_) => None,
         Ok(schedule) => schedule
-            .next_after(&DateTime::<Utc>::from_naive_utc_and_offset(
-                NaiveDateTime::from_timestamp_opt(after, 0).unwrap(),
-                Utc,
-            ))
+            .next_after(&timezone.timestamp_opt(after, 0).single()?)
             .take()
             .map(|datetime| datetime.timestamp()),
     }
@@ -9,7 +9,10 @@ use solana_program::pubkey::Pubkey;
 use tokio::runtime::{Builder, Runtime};
 
 use crate::{
-    config::PluginConfig, events::AccountUpdateEvent, executors::Executors, observers::Observers,
+    config::PluginConfig,
+    events::{AccountUpdateEvent, OraclePrograms},
+    executors::Executors,
+    observers::Observers,
 };
 
 pub struct ClockworkPlugin {
@@ -27,6 +30,7 @@ pub struct Inner {
     pub config: PluginConfig,
     pub executors: Arc<Executors>,
     pub observers: Arc<Observers>,
+    pub oracle_programs: OraclePrograms,
     pub runtime: Arc<Runtime>,
 }
 
@@ -45,7 +49,7 @@ impl GeyserPlugin for ClockworkPlugin {
         );
         info!("Loading snapshot...");
         let config = PluginConfig::read_from(config_file)?;
-        *self = ClockworkPlugin::new_from_config(config);
+        *self = ClockworkPlugin::new_from_config(config)?;
         Ok(())
     }
 
@@ -88,7 +92,13 @@ impl GeyserPlugin for ClockworkPlugin {
             },
         };
         let account_pubkey = Pubkey::try_from(account_info.pubkey).unwrap();
-        let event = AccountUpdateEvent::try_from(account_info);
+        let account_data = self
+            .inner
+            .observers
+            .thread
+            .observes_account_data(&account_pubkey)
+            .then(|| account_info.data.to_vec());
+        let event = AccountUpdateEvent::try_from_account(account_info, &self.inner.oracle_programs);
 
         // Process event on tokio task.
         self.inner.clone().spawn(|inner| async move {
@@ -99,7 +109,7 @@ impl GeyserPlugin for ClockworkPlugin {
                     .observers
                     .thread
                     .clone()
-                    .observe_account(account_pubkey, slot)
+                    .observe_account(account_pubkey, account_data, slot)
                     .await?;
             }
 
@@ -133,12 +143,12 @@ impl GeyserPlugin for ClockworkPlugin {
                             .await
                             .ok();
                     }
-                    AccountUpdateEvent::PriceFeed { price_feed } => {
+                    AccountUpdateEvent::PriceFeed { price } => {
                         inner
                             .observers
                             .thread
                             .clone()
-                            .observe_price_feed(account_pubkey, price_feed)
+                            .observe_price_feed(account_pubkey, price)
                             .await
                             .ok();
                     }
@@ -201,24 +211,26 @@ impl GeyserPlugin for ClockworkPlugin {
 }
 
 impl ClockworkPlugin {
-    fn new_from_config(config: PluginConfig) -> Self {
+    fn new_from_config(config: PluginConfig) -> PluginResult<Self> {
+        let oracle_programs = OraclePrograms::new(&config.oracle_program_ids)?;
         let runtime = build_runtime(config.clone());
         let observers = Arc::new(Observers::new());
         let executors = Arc::new(Executors::new(config.clone()));
-        Self {
+        Ok(Self {
             inner: Arc::new(Inner {
                 config,
                 executors,
                 observers,
+                oracle_programs,
                 runtime,
             }),
-        }
+        })
     }
 }
 
 impl Default for ClockworkPlugin {
     fn default() -> Self {
-        Self::new_from_config(PluginConfig::default())
+        Self::new_from_config(PluginConfig::default()).unwrap()
     }
 }
 
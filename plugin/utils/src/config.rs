@@ -11,6 +11,9 @@ use {
 
 static DEFAULT_TRANSACTION_TIMEOUT_THRESHOLD: u64 = 150;
 static DEFAULT_THREAD_COUNT: usize = 10;
+static PYTH_ORACLE_PROGRAM_ID_MAINNET: &str = "FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH";
+static PYTH_ORACLE_PROGRAM_ID_DEVNET: &str = "gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s";
+static SWITCHBOARD_ORACLE_PROGRAM_ID: &str = "SW1TCH7qEPTdLsDHRgPuMQjbQxKdH2aBStViMFnt64f";
 
 /// Plugin config.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -20,6 +23,42 @@ pub struct PluginConfig {
     pub thread_count: usize,
     pub transaction_timeout_threshold: u64,
     pub worker_id: u64,
+    #[serde(default)]
+    pub oracle_program_ids: OracleProgramIds,
+    #[serde(default)]
+    pub intent_sources: IntentSources,
+}
+
+/// The sources the plugin reads thread intents signed by thread authorities from.
+/// Each intent is a JSON object with the base58-encoded borsh serialization of the intent
+/// and the base58-encoded ed25519 signature of the authority.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct IntentSources {
+    /// An HTTP endpoint which responds to GET requests with a JSON array of intents.
+    pub endpoint: Option<String>,
+    /// A local queue file with one JSON intent per line. Intents are moved out of the queue when it is read,
+    /// and kept in `<queue_path>.pending` until they are submitted or can no longer be executed.
+    pub queue_path: Option<String>,
+}
+
+/// The oracle program IDs of the cluster the plugin runs on.
+/// Price feed accounts owned by these programs are parsed for oracle triggers.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OracleProgramIds {
+    pub pyth: Vec<String>,
+    pub switchboard: Vec<String>,
+}
+
+impl Default for OracleProgramIds {
+    fn default() -> Self {
+        Self {
+            pyth: vec![
+                PYTH_ORACLE_PROGRAM_ID_MAINNET.into(),
+                PYTH_ORACLE_PROGRAM_ID_DEVNET.into(),
+            ],
+            switchboard: vec![SWITCHBOARD_ORACLE_PROGRAM_ID.into()],
+        }
+    }
 }
 
 impl Default for PluginConfig {
@@ -30,6 +69,8 @@ impl Default for PluginConfig {
             transaction_timeout_threshold: DEFAULT_TRANSACTION_TIMEOUT_THRESHOLD,
             thread_count: DEFAULT_THREAD_COUNT,
             worker_id: 0,
+            oracle_program_ids: OracleProgramIds::default(),
+            intent_sources: IntentSources::default(),
         }
     }
 }
@@ -1,3 +1,3 @@
 mod config;
 
-pub use crate::config::PluginConfig;
+pub use crate::config::{IntentSources, OracleProgramIds, PluginConfig};
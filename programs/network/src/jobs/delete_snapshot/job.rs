@@ -40,5 +40,7 @@ pub fn handler(ctx: Context<DeleteSnapshotJob>) -> Result<ThreadResponse> {
         ),
         close_to: None,
         trigger: None,
+        dynamic_instructions: vec![],
+        memory: None,
     })
 }
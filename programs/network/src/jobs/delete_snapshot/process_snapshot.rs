@@ -71,5 +71,5 @@ pub fn handler(ctx: Context<DeleteSnapshotProcessSnapshot>) -> Result<ThreadResp
         None
     };
 
-    Ok(ThreadResponse { dynamic_instruction, close_to:None, trigger: None })
+    Ok(ThreadResponse { dynamic_instruction, close_to:None, trigger: None, dynamic_instructions: vec![], memory: None })
 }
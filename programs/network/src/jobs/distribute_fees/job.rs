@@ -46,5 +46,7 @@ pub fn handler(ctx: Context<DistributeFeesJob>) -> Result<ThreadResponse> {
         ),
         close_to: None,
         trigger: None,
+        dynamic_instructions: vec![],
+        memory: None,
     })
 }
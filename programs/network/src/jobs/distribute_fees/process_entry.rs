@@ -170,5 +170,7 @@ pub fn handler(ctx: Context<DistributeFeesProcessEntry>) -> Result<ThreadRespons
         dynamic_instruction,
         close_to: None,
         trigger: None,
+        dynamic_instructions: vec![],
+        memory: None,
     })
 }
@@ -145,5 +145,7 @@ pub fn handler(ctx: Context<DistributeFeesProcessFrame>) -> Result<ThreadRespons
         dynamic_instruction,
         close_to: None,
         trigger: None,
+        dynamic_instructions: vec![],
+        memory: None,
     })
 }
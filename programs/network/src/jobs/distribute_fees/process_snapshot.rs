@@ -69,5 +69,7 @@ pub fn handler(ctx: Context<DistributeFeesProcessSnapshot>) -> Result<ThreadResp
         },
         close_to: None,
         trigger: None,
+        dynamic_instructions: vec![],
+        memory: None,
     })
 }
@@ -28,5 +28,7 @@ pub fn handler(ctx: Context<EpochCutover>) -> Result<ThreadResponse> {
         close_to: None,
         dynamic_instruction: None,
         trigger: None,
+        dynamic_instructions: vec![],
+        memory: None,
     })
 }
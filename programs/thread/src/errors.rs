@@ -43,4 +43,97 @@ pub enum ClockworkError {
     /// Thrown if the user attempts to withdraw SOL that would put a thread below it's minimum rent threshold.
     #[msg("Withdrawing this amount would leave the thread with less than the minimum required SOL for rent exemption")]
     WithdrawalTooLarge,
+
+    /// Thrown if a delegate attempts to withdraw more SOL than it has been permitted to.
+    #[msg("Withdrawing this amount would exceed the delegate's withdraw limit")]
+    WithdrawLimitExceeded,
+
+    /// Thrown if a composite trigger is nested too deeply, has too many child triggers, or contains a signal trigger.
+    #[msg("The composite trigger is invalid")]
+    InvalidCompositeTrigger,
+
+    /// Thrown if a thread's funding settings have a floor above their target, or an empty period.
+    #[msg("The funding floor cannot exceed its target, and its period must be positive")]
+    InvalidFundingSettings,
+
+    /// Thrown if an instruction's data patch is out of bounds or its value is unavailable,
+    /// or if patches are set for an instruction the thread does not have.
+    #[msg("The instruction's data patch could not be applied")]
+    InvalidDataPatch,
+
+    /// Thrown if a thread authority attempts to set a memory size above the maximum allowed value.
+    #[msg("Thread memory size cannot exceed the maximum allowed value")]
+    InvalidMemorySize,
+
+    /// Thrown if a thread declares too many signers or a signer seed is too long,
+    /// or an instruction references a signer the thread has not declared.
+    #[msg("The thread signer is invalid")]
+    InvalidThreadSigner,
+
+    /// Thrown if an intent is for another thread or is not signed by the thread's authority.
+    #[msg("The intent is not signed by the thread's authority")]
+    InvalidIntent,
+
+    /// Thrown if an intent's nonce is not greater than the nonce of the thread's last executed intent.
+    #[msg("The intent's nonce has already been used")]
+    InvalidIntentNonce,
+
+    /// Thrown if an intent is executed after its expiry.
+    #[msg("The intent has expired")]
+    IntentExpired,
+
+    /// Thrown if a thread response would queue more dynamic instructions than a thread can hold.
+    #[msg("The thread cannot queue any more dynamic instructions")]
+    TooManyDynamicInstructions,
+
+    /// Thrown if a worker reports more than one exec failure for a thread in the same slot.
+    #[msg("A failure has already been reported for this thread in the current slot")]
+    FailureAlreadyReported,
+
+    /// Thrown if an exec would cause a thread to exceed one of its spending limits.
+    #[msg("The thread's spending limit has been exceeded")]
+    SpendingLimitExceeded,
+
+    /// Thrown if the signer is neither the thread's authority nor a delegate with the required permission.
+    #[msg("The signer is not authorized to perform this operation on the thread")]
+    UnauthorizedSigner,
+
+    /// Thrown if a signal's payload exceeds the maximum allowed size.
+    #[msg("The signal payload exceeds the maximum allowed size")]
+    SignalPayloadTooLarge,
+
+    /// Thrown if a thread is signaled while a prior signal has not yet kicked it off.
+    #[msg("The thread has already been signaled")]
+    ThreadAlreadySignaled,
+
+    /// Thrown if a thread response writes past the end of the thread's memory.
+    #[msg("The memory write exceeds the size of the thread's memory")]
+    MemoryWriteTooLarge,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_error_codes() {
+        // Clients and workers match on error codes, so existing variants must keep their positions.
+        let codes = [
+            ClockworkError::InvalidThreadResponse,
+            ClockworkError::InvalidThreadState,
+            ClockworkError::InvalidTriggerVariant,
+            ClockworkError::TriggerConditionFailed,
+            ClockworkError::ThreadBusy,
+            ClockworkError::ThreadPaused,
+            ClockworkError::RateLimitExeceeded,
+            ClockworkError::MaxRateLimitExceeded,
+            ClockworkError::UnauthorizedWrite,
+            ClockworkError::WithdrawalTooLarge,
+        ]
+        .map(u32::from);
+        assert_eq!(
+            [6000, 6001, 6002, 6003, 6004, 6005, 6006, 6007, 6008, 6009],
+            codes
+        );
+    }
 }
@@ -0,0 +1,64 @@
+use std::mem::size_of;
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::system_program,
+    system_program::{transfer, Transfer},
+};
+
+use crate::state::*;
+
+/// Accounts required by the `fund_create` instruction.
+#[derive(Accounts)]
+pub struct FundCreate<'info> {
+    /// The authority (owner) of the fund.
+    #[account()]
+    pub authority: Signer<'info>,
+
+    /// The fund to be created.
+    #[account(
+        init,
+        seeds = [
+            SEED_FUND,
+            authority.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<Fund>(),
+    )]
+    pub fund: Account<'info, Fund>,
+
+    /// The payer for account initializations.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The Solana system program.
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<FundCreate>, amount: u64) -> Result<()> {
+    // Get accounts
+    let authority = &ctx.accounts.authority;
+    let fund = &mut ctx.accounts.fund;
+    let payer = &ctx.accounts.payer;
+    let system_program = &ctx.accounts.system_program;
+
+    // Initialize the fund
+    fund.authority = authority.key();
+    fund.bump = ctx.bumps.fund;
+
+    // Transfer SOL from payer to the fund.
+    transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            Transfer {
+                from: payer.to_account_info(),
+                to: fund.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    Ok(())
+}
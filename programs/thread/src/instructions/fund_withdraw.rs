@@ -0,0 +1,58 @@
+use {
+    crate::{errors::*, state::*},
+    anchor_lang::prelude::*,
+};
+
+/// Accounts required by the `fund_withdraw` instruction.
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct FundWithdraw<'info> {
+    /// The authority (owner) of the fund.
+    #[account()]
+    pub authority: Signer<'info>,
+
+    /// The fund to withdraw from.
+    #[account(
+        mut,
+        seeds = [
+            SEED_FUND,
+            authority.key().as_ref(),
+        ],
+        bump = fund.bump,
+        has_one = authority,
+    )]
+    pub fund: Account<'info, Fund>,
+
+    /// The account to withdraw lamports to.
+    #[account(mut)]
+    pub pay_to: SystemAccount<'info>,
+}
+
+pub fn handler(ctx: Context<FundWithdraw>, amount: u64) -> Result<()> {
+    // Get accounts
+    let fund = &mut ctx.accounts.fund;
+    let pay_to = &mut ctx.accounts.pay_to;
+
+    // Calculate the minimum rent threshold
+    let data_len = fund.to_account_info().data_len();
+    let minimum_rent = Rent::get().unwrap().minimum_balance(data_len);
+    let post_balance = fund
+        .to_account_info()
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ClockworkError::WithdrawalTooLarge)?;
+    require!(
+        post_balance.ge(&minimum_rent),
+        ClockworkError::WithdrawalTooLarge
+    );
+
+    // Withdraw balance from the fund to the pay_to account
+    **fund.to_account_info().try_borrow_mut_lamports()? = post_balance;
+    **pay_to.to_account_info().try_borrow_mut_lamports()? = pay_to
+        .to_account_info()
+        .lamports()
+        .checked_add(amount)
+        .unwrap();
+
+    Ok(())
+}
@@ -1,25 +1,45 @@
+pub mod fund_create;
+pub mod fund_withdraw;
 pub mod get_crate_info;
+pub mod thread_authority_accept;
+pub mod thread_authority_transfer;
 pub mod thread_create;
+pub mod thread_delegate_grant;
+pub mod thread_delegate_revoke;
 pub mod thread_delete;
 pub mod thread_exec;
+pub mod thread_exec_intent;
+pub mod thread_funding_update;
 pub mod thread_instruction_add;
 pub mod thread_instruction_remove;
 pub mod thread_kickoff;
 pub mod thread_pause;
+pub mod thread_report_failure;
 pub mod thread_reset;
 pub mod thread_resume;
+pub mod thread_signal;
 pub mod thread_update;
 pub mod thread_withdraw;
 
+pub use fund_create::*;
+pub use fund_withdraw::*;
 pub use get_crate_info::*;
+pub use thread_authority_accept::*;
+pub use thread_authority_transfer::*;
 pub use thread_create::*;
+pub use thread_delegate_grant::*;
+pub use thread_delegate_revoke::*;
 pub use thread_delete::*;
 pub use thread_exec::*;
+pub use thread_exec_intent::*;
+pub use thread_funding_update::*;
 pub use thread_instruction_add::*;
 pub use thread_instruction_remove::*;
 pub use thread_kickoff::*;
 pub use thread_pause::*;
+pub use thread_report_failure::*;
 pub use thread_reset::*;
 pub use thread_resume::*;
+pub use thread_signal::*;
 pub use thread_update::*;
 pub use thread_withdraw::*;
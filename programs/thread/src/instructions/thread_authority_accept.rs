@@ -0,0 +1,40 @@
+use {
+    crate::{errors::*, state::*},
+    anchor_lang::prelude::*,
+};
+
+/// Accounts required by the `thread_authority_accept` instruction.
+#[derive(Accounts)]
+pub struct ThreadAuthorityAccept<'info> {
+    /// The new authority (owner) of the thread.
+    #[account()]
+    pub new_authority: Signer<'info>,
+
+    /// The thread to be transferred.
+    #[account(
+        mut,
+        seeds = [
+            SEED_THREAD,
+            thread.authority.as_ref(),
+            thread.id.as_slice(),
+        ],
+        bump = thread.bump,
+        constraint = thread.pending_authority.eq(&Some(new_authority.key())) @ ClockworkError::UnauthorizedSigner
+    )]
+    pub thread: Account<'info, Thread>,
+}
+
+pub fn handler(ctx: Context<ThreadAuthorityAccept>) -> Result<()> {
+    // Get accounts
+    let new_authority = &ctx.accounts.new_authority;
+    let thread = &mut ctx.accounts.thread;
+
+    // Hand control of the thread to the new authority.
+    thread.controlling_authority = Some(new_authority.key());
+    thread.pending_authority = None;
+
+    // Delegates were granted by the prior authority, so revoke them.
+    thread.delegates = vec![];
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use {
+    crate::{errors::*, state::*},
+    anchor_lang::prelude::*,
+};
+
+/// Accounts required by the `thread_authority_transfer` instruction.
+#[derive(Accounts)]
+#[instruction(new_authority: Pubkey)]
+pub struct ThreadAuthorityTransfer<'info> {
+    /// The authority (owner) of the thread.
+    #[account()]
+    pub authority: Signer<'info>,
+
+    /// The thread to be transferred.
+    #[account(
+        mut,
+        seeds = [
+            SEED_THREAD,
+            thread.authority.as_ref(),
+            thread.id.as_slice(),
+        ],
+        bump = thread.bump,
+        constraint = thread.current_authority().eq(&authority.key()) @ ClockworkError::UnauthorizedSigner
+    )]
+    pub thread: Account<'info, Thread>,
+}
+
+pub fn handler(ctx: Context<ThreadAuthorityTransfer>, new_authority: Pubkey) -> Result<()> {
+    // Get accounts
+    let thread = &mut ctx.accounts.thread;
+
+    // Record the new authority. The transfer completes once the new authority accepts it.
+    // Proposing the current authority cancels any pending transfer.
+    if new_authority.eq(&thread.current_authority()) {
+        thread.pending_authority = None;
+    } else {
+        thread.pending_authority = Some(new_authority);
+    }
+
+    Ok(())
+}
@@ -44,19 +44,21 @@ pub struct ThreadCreate<'info> {
             id.len(),
             instructions.try_to_vec()?.len(),  
             trigger.try_to_vec()?.len(),
-            NEXT_INSTRUCTION_SIZE,
         ].iter().sum()
     )]
     pub thread: Account<'info, Thread>,
 }
 
-pub fn handler(ctx: Context<ThreadCreate>, amount: u64, id: Vec<u8>, instructions: Vec<SerializableInstruction>, trigger: Trigger) -> Result<()> {
+pub fn handler(ctx: Context<ThreadCreate>, amount: u64, id: Vec<u8>, instructions: Vec<SerializableInstruction>, trigger: Trigger, limits: Option<ThreadLimits>) -> Result<()> {
     // Get accounts
     let authority = &ctx.accounts.authority;
     let payer = &ctx.accounts.payer;
     let system_program = &ctx.accounts.system_program;
     let thread = &mut ctx.accounts.thread;
 
+    // Verify the trigger's composite triggers are within bounds.
+    validate_trigger(&trigger)?;
+
     // Initialize the thread
     thread.authority = authority.key();
     thread.bump = ctx.bumps.thread;
@@ -70,6 +72,22 @@ pub fn handler(ctx: Context<ThreadCreate>, amount: u64, id: Vec<u8>, instruction
     thread.paused = false;
     thread.rate_limit = u64::MAX;
     thread.trigger = trigger;
+    thread.limits = limits.unwrap_or_default();
+    thread.lookup_tables = vec![];
+    thread.dynamic_instructions = vec![];
+    thread.delegates = vec![];
+    thread.controlling_authority = None;
+    thread.pending_authority = None;
+    thread.failure_policy = FailurePolicy::default();
+    thread.priority_fee = PriorityFee::default();
+    thread.funding = None;
+    thread.spending_limits = SpendingLimits::default();
+    thread.stats = ThreadStats::default();
+    thread.memory = vec![];
+    thread.signers = vec![];
+    thread.intent_nonce = 0;
+    thread.exec_counters = ExecCounters::default();
+    thread.instruction_patches = vec![];
 
     // Transfer SOL from payer to the thread.
     transfer(
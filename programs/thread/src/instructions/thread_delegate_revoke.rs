@@ -0,0 +1,36 @@
+use {
+    crate::{errors::*, state::*},
+    anchor_lang::prelude::*,
+};
+
+/// Accounts required by the `thread_delegate_revoke` instruction.
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct ThreadDelegateRevoke<'info> {
+    /// The authority (owner) of the thread.
+    #[account()]
+    pub authority: Signer<'info>,
+
+    /// The thread to revoke the delegate from.
+    #[account(
+        mut,
+        seeds = [
+            SEED_THREAD,
+            thread.authority.as_ref(),
+            thread.id.as_slice(),
+        ],
+        bump = thread.bump,
+        constraint = thread.current_authority().eq(&authority.key()) @ ClockworkError::UnauthorizedSigner
+    )]
+    pub thread: Account<'info, Thread>,
+}
+
+pub fn handler(ctx: Context<ThreadDelegateRevoke>, address: Pubkey) -> Result<()> {
+    // Get accounts
+    let thread = &mut ctx.accounts.thread;
+
+    // Remove the delegate.
+    thread.delegates.retain(|d| d.address.ne(&address));
+
+    Ok(())
+}
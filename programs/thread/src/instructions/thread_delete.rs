@@ -5,7 +5,7 @@ use {crate::state::*, anchor_lang::prelude::*};
 pub struct ThreadDelete<'info> {
     /// The authority (owner) of the thread.
     #[account(
-        constraint = authority.key().eq(&thread.authority) || authority.key().eq(&thread.key())
+        constraint = authority.key().eq(&thread.current_authority()) || authority.key().eq(&thread.key())
     )]
     pub authority: Signer<'info>,
 
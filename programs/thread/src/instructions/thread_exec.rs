@@ -3,20 +3,32 @@ use anchor_lang::{
     solana_program::{
         instruction::Instruction,
         program::{get_return_data, invoke_signed},
+        sysvar::instructions::{
+            load_current_index_checked, load_instruction_at_checked, ID as INSTRUCTIONS_SYSVAR_ID,
+        },
     },
-    AnchorDeserialize, InstructionData,
+    AnchorDeserialize, Discriminator, InstructionData,
 };
 use clockwork_network_program::state::{Fee, Pool, Worker, WorkerAccount};
-use clockwork_utils::thread::{SerializableInstruction, ThreadResponse, PAYER_PUBKEY};
+use clockwork_utils::thread::{
+    thread_signer_index, DataPatch, PatchValue, SerializableInstruction, ThreadResponse,
+    FEE_PUBKEY, PAYER_PUBKEY, THREAD_PUBKEY, WORKER_PUBKEY,
+};
 
 use crate::{errors::ClockworkError, state::*};
 
 /// The ID of the pool workers must be a member of to collect fees.
-const POOL_ID: u64 = 0;
+pub(crate) const POOL_ID: u64 = 0;
 
 /// The number of lamports to reimburse the worker with after they've submitted a transaction's worth of exec instructions.
 pub const TRANSACTION_BASE_FEE_REIMBURSEMENT: u64 = 5_000;
 
+/// The ID of the Solana compute budget program (`ComputeBudget111111111111111111111111111111`).
+const COMPUTE_BUDGET_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    3, 6, 70, 111, 229, 33, 23, 50, 255, 236, 173, 186, 114, 195, 155, 231, 188, 140, 229, 187,
+    197, 247, 18, 107, 44, 67, 155, 58, 64, 0, 0, 0,
+]);
+
 /// Accounts required by the `thread_exec` instruction.
 #[derive(Accounts)]
 pub struct ThreadExec<'info> {
@@ -59,20 +71,38 @@ pub struct ThreadExec<'info> {
     /// The worker.
     #[account(address = worker.pubkey())]
     pub worker: Account<'info, Worker>,
+
+    /// The instructions sysvar, used to read the transaction's priority fee.
+    /// CHECK: The address is checked against the instructions sysvar ID.
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// The fund to top up the thread from, if the thread has funding enabled.
+    #[account(
+        mut,
+        seeds = [
+            SEED_FUND,
+            thread.current_authority().as_ref(),
+        ],
+        bump = fund.bump,
+    )]
+    pub fund: Option<Account<'info, Fund>>,
 }
 
 pub fn handler(ctx: Context<ThreadExec>) -> Result<()> {
     // Get accounts
     let clock = Clock::get().unwrap();
     let fee = &mut ctx.accounts.fee;
+    let fund = &mut ctx.accounts.fund;
+    let instructions = &ctx.accounts.instructions;
     let pool = &ctx.accounts.pool;
     let signatory = &mut ctx.accounts.signatory;
     let thread = &mut ctx.accounts.thread;
     let worker = &ctx.accounts.worker;
 
     // If the rate limit has been met, exit early.
-    if thread.exec_context.unwrap().last_exec_at == clock.slot
-        && thread.exec_context.unwrap().execs_since_slot >= thread.rate_limit
+    if thread.exec_context.as_ref().unwrap().last_exec_at == clock.slot
+        && thread.exec_context.as_ref().unwrap().execs_since_slot >= thread.rate_limit
     {
         return Err(ClockworkError::RateLimitExeceeded.into());
     }
@@ -84,24 +114,31 @@ pub fn handler(ctx: Context<ThreadExec>) -> Result<()> {
     // We have already verified that it is not null during account validation.
     let instruction: &mut SerializableInstruction = &mut thread.next_instruction.clone().unwrap();
 
-    // Inject the signatory's pubkey for the Clockwork payer ID.
-    for acc in instruction.accounts.iter_mut() {
-        if acc.pubkey.eq(&PAYER_PUBKEY) {
-            acc.pubkey = signatory.key();
-        }
+    // Inject the signatory's pubkey for the Clockwork payer ID, and the thread, worker, and fee pubkeys for theirs.
+    inject_placeholder_pubkeys(
+        instruction,
+        signatory.key(),
+        thread.key(),
+        &thread.signers,
+        worker.key(),
+    )?;
+
+    // If the instruction is from the thread's instruction set, write the thread and trigger context values into its data.
+    let exec_context = thread.exec_context.as_ref().unwrap();
+    if thread.instructions.get(exec_context.exec_index as usize) == thread.next_instruction.as_ref()
+    {
+        apply_data_patches(
+            instruction,
+            thread.patches(exec_context.exec_index),
+            thread.key(),
+            exec_context,
+            &thread.exec_counters,
+            &clock,
+        )?;
     }
 
-    // Invoke the provided instruction.
-    invoke_signed(
-        &Instruction::from(&*instruction),
-        ctx.remaining_accounts,
-        &[&[
-            SEED_THREAD,
-            thread.authority.as_ref(),
-            thread.id.as_slice(),
-            &[thread.bump],
-        ]],
-    )?;
+    // Invoke the provided instruction, signing for the thread and its derived signers.
+    invoke_signed_by_thread(thread, &*instruction, ctx.remaining_accounts)?;
 
     // Verify the inner instruction did not write data to the signatory address.
     require!(signatory.data_is_empty(), ClockworkError::UnauthorizedWrite);
@@ -118,12 +155,25 @@ pub fn handler(ctx: Context<ThreadExec>) -> Result<()> {
         }
     };
 
-    // Grab the next instruction from the thread response.
+    // Grab the dynamic instructions from the thread response.
     let mut close_to = None;
-    let mut next_instruction = None;
+    let mut dynamic_instructions = vec![];
     if let Some(thread_response) = thread_response {
         close_to = thread_response.close_to;
-        next_instruction = thread_response.dynamic_instruction;
+        dynamic_instructions = thread_response
+            .dynamic_instruction
+            .into_iter()
+            .chain(thread_response.dynamic_instructions)
+            .collect();
+
+        // Write to the thread's memory.
+        if let Some(memory) = thread_response.memory {
+            thread
+                .memory
+                .get_mut(..memory.len())
+                .ok_or(ClockworkError::MemoryWriteTooLarge)?
+                .copy_from_slice(&memory);
+        }
 
         // Update the trigger.
         if let Some(trigger) = thread_response.trigger {
@@ -131,70 +181,103 @@ pub fn handler(ctx: Context<ThreadExec>) -> Result<()> {
                 std::mem::discriminant(&thread.trigger) == std::mem::discriminant(&trigger),
                 ClockworkError::InvalidTriggerVariant
             );
+            validate_trigger(&trigger)?;
             thread.trigger = trigger.clone();
 
             // If the user updates an account trigger, the trigger context is no longer valid.
             // Here we reset the trigger context to zero to re-prime the trigger.
+            let exec_context = thread.exec_context.clone().unwrap();
             thread.exec_context = Some(ExecContext {
-                trigger_context: match trigger {
-                    Trigger::Account {
-                        address: _,
-                        offset: _,
-                        size: _,
-                    } => TriggerContext::Account { data_hash: 0 },
-                    _ => thread.exec_context.unwrap().trigger_context,
-                },
-                ..thread.exec_context.unwrap()
+                trigger_context: exec_context.trigger_context.reset(&trigger),
+                ..exec_context
             })
         }
     }
 
+    // Queue the new dynamic instructions and pop the next one from the queue.
     // If there is no dynamic next instruction, get the next instruction from the instruction set.
-    let mut exec_index = thread.exec_context.unwrap().exec_index;
+    let mut queue = thread.dynamic_instructions.clone();
+    let mut exec_index = thread.exec_context.as_ref().unwrap().exec_index;
+    let mut next_instruction = next_dynamic_instruction(&mut queue, dynamic_instructions)?;
     if next_instruction.is_none() {
         if let Some(ix) = thread.instructions.get((exec_index + 1) as usize) {
             next_instruction = Some(ix.clone());
-            exec_index = exec_index + 1;
+            exec_index += 1;
         }
     }
 
-    // Update the next instruction.
-    if let Some(close_to) = close_to {
-        thread.next_instruction = Some(
-            Instruction {
-                program_id: crate::ID,
-                accounts: crate::accounts::ThreadDelete {
-                    authority: thread.key(),
-                    close_to,
-                    thread: thread.key(),
-                }
-                .to_account_metas(Some(true)),
-                data: crate::instruction::ThreadDelete {}.data(),
-            }
-            .into(),
-        );
-    } else {
-        thread.next_instruction = next_instruction;
-    }
-
     // Update the exec context.
-    let should_reimburse_transaction = clock.slot > thread.exec_context.unwrap().last_exec_at;
+    let exec_context = thread.exec_context.clone().unwrap();
+    let should_reimburse_transaction = clock.slot > exec_context.last_exec_at;
     thread.exec_context = Some(ExecContext {
         exec_index,
-        execs_since_slot: if clock.slot == thread.exec_context.unwrap().last_exec_at {
-            thread
-                .exec_context
-                .unwrap()
-                .execs_since_slot
-                .checked_add(1)
-                .unwrap()
+        execs_since_slot: if clock.slot == exec_context.last_exec_at {
+            exec_context.execs_since_slot.checked_add(1).unwrap()
         } else {
             1
         },
         last_exec_at: clock.slot,
-        ..thread.exec_context.unwrap()
+        ..exec_context
     });
 
+    // Update the exec counters.
+    let exec_counters = thread.exec_counters.clone();
+    thread.exec_counters = ExecCounters {
+        consecutive_failures: 0,
+        execs_since_creation: exec_counters.execs_since_creation.checked_add(1).unwrap(),
+        runs_completed: if next_instruction.is_none() {
+            exec_counters.runs_completed.checked_add(1).unwrap()
+        } else {
+            exec_counters.runs_completed
+        },
+        ..exec_counters
+    };
+
+    // If the thread has reached its limits, close it to the configured address.
+    if close_to.is_none() && thread.is_expired(&clock) {
+        close_to = Some(thread.limits.close_to.unwrap_or(thread.current_authority()));
+    }
+
+    // Update the next instruction and the dynamic instruction queue.
+    if let Some(close_to) = close_to {
+        thread.next_instruction = Some(build_thread_delete_instruction(thread.key(), close_to));
+        thread.dynamic_instructions = vec![];
+    } else {
+        thread.next_instruction = next_instruction;
+        thread.dynamic_instructions = queue;
+    }
+
+    // Realloc the thread account to fit the queued instructions.
+    thread.realloc()?;
+
+    // If the thread's balance has fallen below its funding floor, top it up from the fund.
+    if let (Some(mut funding), Some(fund)) = (thread.funding.clone(), fund.as_mut()) {
+        if close_to.is_none() {
+            let fund_rent = Rent::get()
+                .unwrap()
+                .minimum_balance(fund.to_account_info().data_len());
+            let available = fund.to_account_info().lamports().saturating_sub(fund_rent);
+            let amount = funding.top_up(
+                thread.to_account_info().lamports(),
+                available,
+                clock.unix_timestamp,
+            );
+            thread.funding = Some(funding);
+            if amount.gt(&0) {
+                **fund.to_account_info().try_borrow_mut_lamports()? = fund
+                    .to_account_info()
+                    .lamports()
+                    .checked_sub(amount)
+                    .unwrap();
+                **thread.to_account_info().try_borrow_mut_lamports()? = thread
+                    .to_account_info()
+                    .lamports()
+                    .checked_add(amount)
+                    .unwrap();
+            }
+        }
+    }
+
     // Reimbursement signatory for lamports paid during inner ix.
     let signatory_lamports_post = signatory.lamports();
     let mut signatory_reimbursement =
@@ -202,8 +285,30 @@ pub fn handler(ctx: Context<ThreadExec>) -> Result<()> {
     if should_reimburse_transaction {
         signatory_reimbursement = signatory_reimbursement
             .checked_add(TRANSACTION_BASE_FEE_REIMBURSEMENT)
+            .unwrap()
+            .checked_add(priority_fee_reimbursement(
+                instructions,
+                &thread.priority_fee,
+            )?)
             .unwrap();
     }
+
+    // Verify the exec's spending is within the thread's limits.
+    let is_worker_in_pool = pool.clone().into_inner().workers.contains(&worker.key());
+    let spent = if is_worker_in_pool {
+        signatory_reimbursement.checked_add(thread.fee).unwrap()
+    } else {
+        signatory_reimbursement
+    };
+    let spending_limits = thread.spending_limits.clone();
+    record_spending(
+        &mut thread.exec_counters,
+        &spending_limits,
+        signatory_reimbursement,
+        spent,
+        clock.slot,
+    )?;
+
     if signatory_reimbursement.gt(&0) {
         **thread.to_account_info().try_borrow_mut_lamports()? = thread
             .to_account_info()
@@ -218,7 +323,7 @@ pub fn handler(ctx: Context<ThreadExec>) -> Result<()> {
     }
 
     // If the worker is in the pool, debit from the thread account and payout to the worker's fee account.
-    if pool.clone().into_inner().workers.contains(&worker.key()) {
+    if is_worker_in_pool {
         **thread.to_account_info().try_borrow_mut_lamports()? = thread
             .to_account_info()
             .lamports()
@@ -231,5 +336,419 @@ pub fn handler(ctx: Context<ThreadExec>) -> Result<()> {
             .unwrap();
     }
 
+    // Update the thread's lifetime stats.
+    let fees_paid = if is_worker_in_pool { thread.fee } else { 0 };
+    thread.stats = ThreadStats {
+        execs: thread.stats.execs.checked_add(1).unwrap(),
+        fees_paid: thread.stats.fees_paid.saturating_add(fees_paid),
+        last_success_at: Some(clock.slot),
+        last_worker: Some(worker.key()),
+        reimbursements: thread
+            .stats
+            .reimbursements
+            .saturating_add(signatory_reimbursement),
+        ..thread.stats.clone()
+    };
+
+    Ok(())
+}
+
+/// Queues the dynamic instructions returned by a thread response behind those already queued, so they execute in order.
+/// Returns the next dynamic instruction to execute, if any.
+fn next_dynamic_instruction(
+    queue: &mut Vec<SerializableInstruction>,
+    dynamic_instructions: Vec<SerializableInstruction>,
+) -> Result<Option<SerializableInstruction>> {
+    queue.extend(dynamic_instructions);
+    require!(
+        queue.len() <= MAX_DYNAMIC_INSTRUCTIONS,
+        ClockworkError::TooManyDynamicInstructions
+    );
+    if queue.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(queue.remove(0)))
+    }
+}
+
+/// Records the lamports spent by an exec against the thread's spending window,
+/// and verifies the exec is within the thread's spending limits.
+pub(crate) fn record_spending(
+    exec_counters: &mut ExecCounters,
+    spending_limits: &SpendingLimits,
+    reimbursement: u64,
+    spent: u64,
+    slot: u64,
+) -> Result<()> {
+    if let Some(max_reimbursement_per_exec) = spending_limits.max_reimbursement_per_exec {
+        require!(
+            reimbursement.le(&max_reimbursement_per_exec),
+            ClockworkError::SpendingLimitExceeded
+        );
+    }
+
+    // If the current window has elapsed, start a new one.
+    if slot.saturating_sub(exec_counters.window_started_at) >= spending_limits.window_slots {
+        exec_counters.window_spent = 0;
+        exec_counters.window_started_at = slot;
+    }
+    exec_counters.window_spent = exec_counters.window_spent.saturating_add(spent);
+    if let Some(max_spent_per_window) = spending_limits.max_spent_per_window {
+        require!(
+            exec_counters.window_spent.le(&max_spent_per_window),
+            ClockworkError::SpendingLimitExceeded
+        );
+    }
     Ok(())
 }
+
+/// Invokes an instruction, signing for the thread and its derived signers.
+pub(crate) fn invoke_signed_by_thread(
+    thread: &Account<Thread>,
+    instruction: &SerializableInstruction,
+    remaining_accounts: &[AccountInfo],
+) -> Result<()> {
+    let thread_pubkey = thread.key();
+    let thread_bump = [thread.bump];
+    let signer_bumps: Vec<[u8; 1]> = thread.signers.iter().map(|s| [s.bump]).collect();
+    let signer_seeds: Vec<Vec<&[u8]>> = std::iter::once(vec![
+        SEED_THREAD,
+        thread.authority.as_ref(),
+        thread.id.as_slice(),
+        &thread_bump,
+    ])
+    .chain(
+        thread
+            .signers
+            .iter()
+            .zip(signer_bumps.iter())
+            .map(|(signer, bump)| {
+                vec![
+                    SEED_THREAD_SIGNER,
+                    thread_pubkey.as_ref(),
+                    signer.seed.as_slice(),
+                    bump,
+                ]
+            }),
+    )
+    .collect();
+    invoke_signed(
+        &Instruction::from(instruction),
+        remaining_accounts,
+        &signer_seeds
+            .iter()
+            .map(|s| s.as_slice())
+            .collect::<Vec<_>>(),
+    )?;
+    Ok(())
+}
+
+/// Returns the priority fee paid by the current transaction, capped by the thread's priority fee settings.
+/// The fee is read from the transaction's compute budget instructions via the instructions sysvar.
+pub(crate) fn priority_fee_reimbursement(
+    instructions: &AccountInfo,
+    priority_fee: &PriorityFee,
+) -> Result<u64> {
+    if priority_fee.max_compute_unit_price.eq(&0) || priority_fee.max_fee_per_tx.eq(&0) {
+        return Ok(0);
+    }
+
+    // Find the compute unit limit and price set by the transaction.
+    // The priority fee is paid once per transaction, so only the first reimbursing instruction in the transaction pays it.
+    let current_index = load_current_index_checked(instructions)? as usize;
+    let mut compute_unit_limit: Option<u64> = None;
+    let mut compute_unit_price: Option<u64> = None;
+    let mut index = 0;
+    while let Ok(ix) = load_instruction_at_checked(index, instructions) {
+        if index.lt(&current_index) && is_reimbursing_instruction(&ix) {
+            return Ok(0);
+        }
+        if ix.program_id.eq(&COMPUTE_BUDGET_PROGRAM_ID) {
+            match ix.data.split_first() {
+                Some((2, data)) => {
+                    compute_unit_limit = data
+                        .try_into()
+                        .ok()
+                        .map(|bytes| u32::from_le_bytes(bytes) as u64);
+                }
+                Some((3, data)) => {
+                    compute_unit_price = data.try_into().ok().map(u64::from_le_bytes);
+                }
+                _ => {}
+            }
+        }
+        index += 1;
+    }
+
+    // The priority fee is the compute unit limit multiplied by the price, rounded up to the nearest lamport.
+    let fee = match (compute_unit_limit, compute_unit_price) {
+        (Some(limit), Some(price)) => (limit as u128)
+            .checked_mul(price.min(priority_fee.max_compute_unit_price) as u128)
+            .unwrap()
+            .checked_add(999_999)
+            .unwrap()
+            .checked_div(1_000_000)
+            .unwrap()
+            .min(priority_fee.max_fee_per_tx as u128) as u64,
+        _ => 0,
+    };
+    Ok(fee)
+}
+
+/// Returns true if the instruction is a thread kickoff or exec, which reimburse the transaction's priority fee.
+fn is_reimbursing_instruction(ix: &Instruction) -> bool {
+    ix.program_id.eq(&crate::ID)
+        && ix.data.get(..8).map_or(false, |discriminator| {
+            discriminator.eq(&crate::instruction::ThreadExec::DISCRIMINATOR)
+                || discriminator.eq(&crate::instruction::ThreadKickoff::DISCRIMINATOR)
+        })
+}
+
+/// Replaces the Clockwork placeholder pubkeys in an instruction's accounts:
+/// the payer with the signatory, the thread, worker, and fee placeholders with their accounts, and the signer placeholders with the thread's derived signers.
+pub(crate) fn inject_placeholder_pubkeys(
+    instruction: &mut SerializableInstruction,
+    signatory: Pubkey,
+    thread_pubkey: Pubkey,
+    signers: &[ThreadSigner],
+    worker: Pubkey,
+) -> Result<()> {
+    for acc in instruction.accounts.iter_mut() {
+        if acc.pubkey.eq(&PAYER_PUBKEY) {
+            acc.pubkey = signatory;
+        } else if acc.pubkey.eq(&THREAD_PUBKEY) {
+            acc.pubkey = thread_pubkey;
+        } else if acc.pubkey.eq(&WORKER_PUBKEY) {
+            acc.pubkey = worker;
+        } else if acc.pubkey.eq(&FEE_PUBKEY) {
+            acc.pubkey = Fee::pubkey(worker);
+        } else if let Some(index) = thread_signer_index(&acc.pubkey) {
+            acc.pubkey = signers
+                .get(index as usize)
+                .ok_or(ClockworkError::InvalidThreadSigner)?
+                .pubkey(thread_pubkey);
+        }
+    }
+    Ok(())
+}
+
+/// Writes the values of the given data patches into an instruction's data.
+fn apply_data_patches(
+    instruction: &mut SerializableInstruction,
+    patches: &[DataPatch],
+    thread_pubkey: Pubkey,
+    exec_context: &ExecContext,
+    exec_counters: &ExecCounters,
+    clock: &Clock,
+) -> Result<()> {
+    for patch in patches.iter() {
+        let value = match patch.value {
+            PatchValue::ThreadPubkey => thread_pubkey.to_bytes().to_vec(),
+            PatchValue::ExecIndex => exec_context.exec_index.to_le_bytes().to_vec(),
+            PatchValue::RunsCompleted => exec_counters.runs_completed.to_le_bytes().to_vec(),
+            PatchValue::Slot => clock.slot.to_le_bytes().to_vec(),
+            PatchValue::UnixTimestamp => clock.unix_timestamp.to_le_bytes().to_vec(),
+            _ => exec_context
+                .trigger_context
+                .patch_value(&patch.value)
+                .ok_or(ClockworkError::InvalidDataPatch)?
+                .to_vec(),
+        };
+        let start = patch.offset as usize;
+        let end = start.checked_add(value.len()).unwrap();
+        instruction
+            .data
+            .get_mut(start..end)
+            .ok_or(ClockworkError::InvalidDataPatch)?
+            .copy_from_slice(&value);
+    }
+    Ok(())
+}
+
+/// Builds an instruction for a thread to delete itself and return its lamports to the close_to address.
+pub(crate) fn build_thread_delete_instruction(
+    thread: Pubkey,
+    close_to: Pubkey,
+) -> SerializableInstruction {
+    Instruction {
+        program_id: crate::ID,
+        accounts: crate::accounts::ThreadDelete {
+            authority: thread,
+            close_to,
+            thread,
+        }
+        .to_account_metas(Some(true)),
+        data: crate::instruction::ThreadDelete {}.data(),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clockwork_utils::thread::thread_signer_placeholder;
+
+    fn instruction(data: u8) -> SerializableInstruction {
+        Instruction::new_with_bytes(crate::ID, &[data], vec![]).into()
+    }
+
+    #[test]
+    fn test_is_reimbursing_instruction() {
+        let exec_ix = Instruction {
+            program_id: crate::ID,
+            accounts: vec![],
+            data: crate::instruction::ThreadExec {}.data(),
+        };
+        let kickoff_ix = Instruction {
+            program_id: crate::ID,
+            accounts: vec![],
+            data: crate::instruction::ThreadKickoff {}.data(),
+        };
+        assert!(is_reimbursing_instruction(&exec_ix));
+        assert!(is_reimbursing_instruction(&kickoff_ix));
+
+        // Other instructions, and exec instructions of other programs, do not reimburse the priority fee.
+        let pause_ix = Instruction {
+            program_id: crate::ID,
+            accounts: vec![],
+            data: crate::instruction::ThreadPause {}.data(),
+        };
+        assert!(!is_reimbursing_instruction(&pause_ix));
+        assert!(!is_reimbursing_instruction(&Instruction {
+            program_id: COMPUTE_BUDGET_PROGRAM_ID,
+            ..exec_ix
+        }));
+        assert!(!is_reimbursing_instruction(&Instruction::new_with_bytes(
+            crate::ID,
+            &[1],
+            vec![]
+        )));
+    }
+
+    #[test]
+    fn test_inject_placeholder_pubkeys() {
+        let signatory = Pubkey::new_unique();
+        let thread_pubkey = Pubkey::new_unique();
+        let worker = Pubkey::new_unique();
+        let signers = vec![ThreadSigner::new(thread_pubkey, b"vault".to_vec())];
+        let other = Pubkey::new_unique();
+        let mut instruction: SerializableInstruction = Instruction::new_with_bytes(
+            crate::ID,
+            &[],
+            vec![
+                AccountMeta::new(PAYER_PUBKEY, true),
+                AccountMeta::new(THREAD_PUBKEY, false),
+                AccountMeta::new_readonly(WORKER_PUBKEY, false),
+                AccountMeta::new(FEE_PUBKEY, false),
+                AccountMeta::new(thread_signer_placeholder(0), false),
+                AccountMeta::new_readonly(other, false),
+            ],
+        )
+        .into();
+        inject_placeholder_pubkeys(&mut instruction, signatory, thread_pubkey, &signers, worker)
+            .unwrap();
+        assert_eq!(
+            instruction
+                .accounts
+                .iter()
+                .map(|acc| acc.pubkey)
+                .collect::<Vec<Pubkey>>(),
+            vec![
+                signatory,
+                thread_pubkey,
+                worker,
+                Fee::pubkey(worker),
+                signers[0].pubkey(thread_pubkey),
+                other,
+            ]
+        );
+
+        // A placeholder for a signer the thread does not have is rejected.
+        let mut instruction: SerializableInstruction = Instruction::new_with_bytes(
+            crate::ID,
+            &[],
+            vec![AccountMeta::new(thread_signer_placeholder(1), false)],
+        )
+        .into();
+        assert!(inject_placeholder_pubkeys(
+            &mut instruction,
+            signatory,
+            thread_pubkey,
+            &signers,
+            worker
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_next_dynamic_instruction_in_order() {
+        let mut queue = vec![];
+
+        // The first response queues two instructions, and the first of them executes next.
+        let next = next_dynamic_instruction(&mut queue, vec![instruction(1), instruction(2)]);
+        assert_eq!(Some(instruction(1)), next.unwrap());
+
+        // The second response queues its instruction behind the one still pending.
+        let next = next_dynamic_instruction(&mut queue, vec![instruction(3)]);
+        assert_eq!(Some(instruction(2)), next.unwrap());
+        assert_eq!(vec![instruction(3)], queue);
+
+        let next = next_dynamic_instruction(&mut queue, vec![]);
+        assert_eq!(Some(instruction(3)), next.unwrap());
+        assert_eq!(None, next_dynamic_instruction(&mut queue, vec![]).unwrap());
+    }
+
+    #[test]
+    fn test_next_dynamic_instruction_queue_full() {
+        let mut queue = vec![instruction(0); MAX_DYNAMIC_INSTRUCTIONS];
+        assert!(next_dynamic_instruction(&mut queue, vec![instruction(1)]).is_err());
+    }
+
+    #[test]
+    fn test_apply_data_patches() {
+        let thread_pubkey = Pubkey::new_unique();
+        let exec_context = ExecContext {
+            exec_index: 0,
+            execs_since_reimbursement: 0,
+            execs_since_slot: 0,
+            last_exec_at: 0,
+            trigger_context: TriggerContext::Cron { started_at: 7 },
+        };
+        let clock = Clock {
+            slot: 5,
+            ..Clock::default()
+        };
+        let patch = |offset, value| DataPatch { offset, value };
+        let apply = |instruction: &mut SerializableInstruction, patches: &[DataPatch]| {
+            apply_data_patches(
+                instruction,
+                patches,
+                thread_pubkey,
+                &exec_context,
+                &ExecCounters::default(),
+                &clock,
+            )
+        };
+
+        // Values are written at their offsets, leaving the rest of the data untouched.
+        let mut ix: SerializableInstruction =
+            Instruction::new_with_bytes(crate::ID, &[9; 49], vec![]).into();
+        apply(
+            &mut ix,
+            &[
+                patch(0, PatchValue::ThreadPubkey),
+                patch(32, PatchValue::Slot),
+                patch(40, PatchValue::TriggerStartedAt),
+            ],
+        )
+        .unwrap();
+        assert_eq!(thread_pubkey.to_bytes(), ix.data[0..32]);
+        assert_eq!(5u64.to_le_bytes(), ix.data[32..40]);
+        assert_eq!(7i64.to_le_bytes(), ix.data[40..48]);
+        assert_eq!(9, ix.data[48]);
+
+        // Patches which run past the end of the data, or whose value is unavailable, are rejected.
+        assert!(apply(&mut ix, &[patch(42, PatchValue::Slot)]).is_err());
+        assert!(apply(&mut ix, &[patch(0, PatchValue::TriggerPrice)]).is_err());
+    }
+}
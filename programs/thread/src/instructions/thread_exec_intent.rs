@@ -0,0 +1,139 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::sysvar::instructions::{
+        load_current_index_checked, load_instruction_at_checked, ID as INSTRUCTIONS_SYSVAR_ID,
+    },
+};
+use clockwork_network_program::state::{Pool, Worker, WorkerAccount};
+
+use crate::{errors::ClockworkError, state::*};
+
+use super::{
+    inject_placeholder_pubkeys, invoke_signed_by_thread, record_spending, POOL_ID,
+    TRANSACTION_BASE_FEE_REIMBURSEMENT,
+};
+
+/// Accounts required by the `thread_exec_intent` instruction.
+#[derive(Accounts)]
+#[instruction(intent: ThreadIntent)]
+pub struct ThreadExecIntent<'info> {
+    /// The instructions sysvar, used to read the authority's signature of the intent.
+    /// CHECK: The address is checked against the instructions sysvar ID.
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// The active worker pool.
+    #[account(address = Pool::pubkey(POOL_ID))]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The signatory.
+    #[account(mut)]
+    pub signatory: Signer<'info>,
+
+    /// The thread to execute the intent.
+    #[account(
+        mut,
+        seeds = [
+            SEED_THREAD,
+            thread.authority.as_ref(),
+            thread.id.as_slice(),
+        ],
+        bump = thread.bump,
+        constraint = !thread.paused @ ClockworkError::ThreadPaused,
+        constraint = intent.thread.eq(&thread.key()) @ ClockworkError::InvalidIntent,
+    )]
+    pub thread: Box<Account<'info, Thread>>,
+
+    /// The worker executing the intent. It must be in the pool.
+    #[account(
+        address = worker.pubkey(),
+        has_one = signatory,
+        constraint = pool.workers.contains(&worker.key()) @ ClockworkError::UnauthorizedSigner
+    )]
+    pub worker: Account<'info, Worker>,
+}
+
+pub fn handler(ctx: Context<ThreadExecIntent>, intent: ThreadIntent) -> Result<()> {
+    // Get accounts
+    let clock = Clock::get().unwrap();
+    let instructions = &ctx.accounts.instructions;
+    let signatory = &mut ctx.accounts.signatory;
+    let thread = &mut ctx.accounts.thread;
+    let worker = &ctx.accounts.worker;
+
+    // Verify the intent has not expired or already been executed.
+    require!(
+        clock.unix_timestamp <= intent.expires_at,
+        ClockworkError::IntentExpired
+    );
+    require!(
+        intent.nonce > thread.intent_nonce,
+        ClockworkError::InvalidIntentNonce
+    );
+
+    // Verify the thread's authority signed the intent in the preceding ed25519 program instruction.
+    let current_index = load_current_index_checked(&instructions.to_account_info())? as usize;
+    let verify_ix = current_index
+        .checked_sub(1)
+        .map(|index| load_instruction_at_checked(index, &instructions.to_account_info()))
+        .transpose()?
+        .ok_or(ClockworkError::InvalidIntent)?;
+    require!(
+        intent.is_verified_by(&verify_ix, &thread.current_authority()),
+        ClockworkError::InvalidIntent
+    );
+    thread.intent_nonce = intent.nonce;
+
+    // Record the worker's lamports before invoking the inner ix.
+    let signatory_lamports_pre = signatory.lamports();
+
+    // Inject the signatory's pubkey for the Clockwork payer ID, and the thread, worker, and fee pubkeys for theirs.
+    let mut instruction = intent.instruction;
+    inject_placeholder_pubkeys(
+        &mut instruction,
+        signatory.key(),
+        thread.key(),
+        &thread.signers,
+        worker.key(),
+    )?;
+
+    // Invoke the intent's instruction, signing for the thread and its derived signers.
+    invoke_signed_by_thread(thread, &instruction, ctx.remaining_accounts)?;
+
+    // Verify the inner instruction did not write data to the signatory address.
+    require!(signatory.data_is_empty(), ClockworkError::UnauthorizedWrite);
+
+    // Reimburse the signatory for the transaction fee and lamports paid during the inner ix.
+    let signatory_lamports_post = signatory.lamports();
+    let signatory_reimbursement = signatory_lamports_pre
+        .saturating_sub(signatory_lamports_post)
+        .checked_add(TRANSACTION_BASE_FEE_REIMBURSEMENT)
+        .unwrap();
+
+    // Verify the intent's spending is within the thread's limits.
+    let spending_limits = thread.spending_limits.clone();
+    record_spending(
+        &mut thread.exec_counters,
+        &spending_limits,
+        signatory_reimbursement,
+        signatory_reimbursement,
+        clock.slot,
+    )?;
+
+    **thread.to_account_info().try_borrow_mut_lamports()? = thread
+        .to_account_info()
+        .lamports()
+        .checked_sub(signatory_reimbursement)
+        .unwrap();
+    **signatory.to_account_info().try_borrow_mut_lamports()? = signatory
+        .to_account_info()
+        .lamports()
+        .checked_add(signatory_reimbursement)
+        .unwrap();
+    thread.stats.reimbursements = thread
+        .stats
+        .reimbursements
+        .saturating_add(signatory_reimbursement);
+
+    Ok(())
+}
@@ -4,13 +4,13 @@ use anchor_lang::{
     system_program::{transfer, Transfer},
 };
 
-use crate::state::*;
+use crate::{errors::*, state::*};
 
 /// Accounts required by the `thread_instruction_add` instruction.
 #[derive(Accounts)]
 #[instruction(instruction: SerializableInstruction)]
 pub struct ThreadInstructionAdd<'info> {
-    /// The authority (owner) of the thread.
+    /// The authority (owner) of the thread, or a delegate with permission to edit its instructions.
     #[account(mut)]
     pub authority: Signer<'info>,
 
@@ -27,7 +27,7 @@ pub struct ThreadInstructionAdd<'info> {
             thread.id.as_slice(),
         ],
         bump = thread.bump,
-        has_one = authority
+        constraint = thread.is_authorized(&authority.key(), ThreadPermission::EditInstructions) @ ClockworkError::UnauthorizedSigner
     )]
     pub thread: Account<'info, Thread>,
 }
@@ -1,10 +1,13 @@
-use {crate::state::*, anchor_lang::prelude::*};
+use {
+    crate::{errors::*, state::*},
+    anchor_lang::prelude::*,
+};
 
 /// Accounts required by the `thread_instruction_remove` instruction.
 #[derive(Accounts)]
 #[instruction(index: u64)]
 pub struct ThreadInstructionRemove<'info> {
-    /// The authority (owner) of the thread.
+    /// The authority (owner) of the thread, or a delegate with permission to edit its instructions.
     #[account()]
     pub authority: Signer<'info>,
 
@@ -17,7 +20,7 @@ pub struct ThreadInstructionRemove<'info> {
             thread.id.as_slice(),
         ],
         bump = thread.bump,
-        has_one = authority
+        constraint = thread.is_authorized(&authority.key(), ThreadPermission::EditInstructions) @ ClockworkError::UnauthorizedSigner
     )]
     pub thread: Account<'info, Thread>,
 }
@@ -29,5 +32,15 @@ pub fn handler(ctx: Context<ThreadInstructionRemove>, index: u64) -> Result<()>
     // Pause the thread
     thread.instructions.remove(index as usize);
 
+    // Drop the removed instruction's patches and shift the patches of the instructions after it.
+    thread
+        .instruction_patches
+        .retain(|instruction_patches| instruction_patches.index.ne(&index));
+    for instruction_patches in thread.instruction_patches.iter_mut() {
+        if instruction_patches.index.gt(&index) {
+            instruction_patches.index -= 1;
+        }
+    }
+
     Ok(())
 }
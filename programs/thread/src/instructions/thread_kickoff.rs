@@ -1,19 +1,20 @@
-use std::{
-    collections::hash_map::DefaultHasher,
-    hash::{Hash, Hasher},
-    str::FromStr,
-};
+use std::str::FromStr;
 
 use anchor_lang::prelude::*;
-use chrono::{DateTime, NaiveDateTime, Utc};
-use clockwork_cron::Schedule;
+use chrono::TimeZone;
+use clockwork_cron::{Schedule, Tz};
 use clockwork_network_program::state::{Worker, WorkerAccount};
-use clockwork_utils::thread::Trigger;
+use clockwork_utils::thread::{
+    hash_account_components, hash_account_data, is_price_confident, read_token_amount, Equality,
+    PythMode, Trigger, DEFAULT_PYTH_STALENESS, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
 use pyth_sdk_solana::load_price_feed_from_account_info;
 
 use crate::{errors::*, state::*};
 
-use super::TRANSACTION_BASE_FEE_REIMBURSEMENT;
+use super::{
+    build_thread_delete_instruction, priority_fee_reimbursement, TRANSACTION_BASE_FEE_REIMBURSEMENT,
+};
 
 /// Accounts required by the `thread_kickoff` instruction.
 #[derive(Accounts)]
@@ -39,248 +40,731 @@ pub struct ThreadKickoff<'info> {
     /// The worker.
     #[account(address = worker.pubkey())]
     pub worker: Account<'info, Worker>,
+
+    /// The instructions sysvar, used to read the transaction's priority fee.
+    /// CHECK: The address is checked against the instructions sysvar ID.
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
 }
 
 pub fn handler(ctx: Context<ThreadKickoff>) -> Result<()> {
     // Get accounts.
+    let instructions = &ctx.accounts.instructions;
     let signatory = &mut ctx.accounts.signatory;
     let thread = &mut ctx.accounts.thread;
     let clock = Clock::get().unwrap();
 
-    match thread.trigger.clone() {
+    // Verify the trigger condition.
+    let (trigger_context, is_active) = verify_trigger(
+        &thread.trigger,
+        thread.exec_context.clone().map(|e| e.trigger_context),
+        thread.created_at.unix_timestamp,
+        &clock,
+        ctx.remaining_accounts,
+    )?;
+
+    // If the trigger is not active, the kickoff has only updated the trigger context (e.g. to re-arm a crossing operator or record a baseline).
+    // Record it without starting a new run or reimbursing the signatory.
+    if !is_active {
+        thread.exec_context = Some(match thread.exec_context.clone() {
+            None => ExecContext {
+                exec_index: 0,
+                execs_since_reimbursement: 0,
+                execs_since_slot: 0,
+                last_exec_at: clock.slot,
+                trigger_context,
+            },
+            Some(exec_context) => ExecContext {
+                trigger_context,
+                ..exec_context
+            },
+        });
+        thread.realloc()?;
+        return Ok(());
+    }
+
+    // Set a new exec context.
+    thread.exec_context = Some(ExecContext {
+        exec_index: 0,
+        execs_since_reimbursement: 0,
+        execs_since_slot: 0,
+        last_exec_at: clock.slot,
+        trigger_context,
+    });
+
+    // Update the next instruction to kick off a new run.
+    // If the thread has reached its limits, schedule it to close instead of kicking off a new run.
+    if thread.is_expired(&clock) {
+        let close_to = thread.limits.close_to.unwrap_or(thread.current_authority());
+        thread.next_instruction = Some(build_thread_delete_instruction(thread.key(), close_to));
+    } else if let Some(kickoff_instruction) = thread.instructions.first() {
+        thread.next_instruction = Some(kickoff_instruction.clone());
+        thread.stats.runs = thread.stats.runs.checked_add(1).unwrap();
+    }
+
+    // Realloc the thread account
+    thread.realloc()?;
+
+    // Reimburse signatory for transaction fee, including any priority fee.
+    let reimbursement = TRANSACTION_BASE_FEE_REIMBURSEMENT
+        .checked_add(priority_fee_reimbursement(
+            instructions,
+            &thread.priority_fee,
+        )?)
+        .unwrap();
+    **thread.to_account_info().try_borrow_mut_lamports()? = thread
+        .to_account_info()
+        .lamports()
+        .checked_sub(reimbursement)
+        .unwrap();
+    **signatory.to_account_info().try_borrow_mut_lamports()? = signatory
+        .to_account_info()
+        .lamports()
+        .checked_add(reimbursement)
+        .unwrap();
+    thread.stats.reimbursements = thread.stats.reimbursements.saturating_add(reimbursement);
+
+    Ok(())
+}
+
+/// Verifies a trigger's condition and returns the trigger context to record for it, along with whether the trigger is active.
+/// An inactive trigger may still update its context (e.g. to re-arm a crossing operator).
+/// Composite triggers are verified by recursing into each of their child triggers.
+fn verify_trigger(
+    trigger: &Trigger,
+    trigger_context: Option<TriggerContext>,
+    created_at: i64,
+    clock: &Clock,
+    remaining_accounts: &[AccountInfo],
+) -> Result<(TriggerContext, bool)> {
+    match trigger.clone() {
         Trigger::Account {
             address,
             offset,
             size,
         } => {
             // Verify proof that account data has been updated.
-            match ctx.remaining_accounts.first() {
-                None => {
-                    return Err(ClockworkError::TriggerConditionFailed.into());
-                }
-                Some(account_info) => {
-                    // Verify the remaining account is the account this thread is listening for.
-                    require!(
-                        address.eq(account_info.key),
-                        ClockworkError::TriggerConditionFailed
-                    );
-
-                    // Begin computing the data hash of this account.
-                    let mut hasher = DefaultHasher::new();
-                    let data = &account_info.try_borrow_data().unwrap();
-                    let offset = offset as usize;
-                    let range_end = offset.checked_add(size as usize).unwrap() as usize;
-                    if data.len().gt(&range_end) {
-                        data[offset..range_end].hash(&mut hasher);
-                    } else {
-                        data[offset..].hash(&mut hasher)
-                    }
-                    let data_hash = hasher.finish();
-
-                    // Verify the data hash is different than the prior data hash.
-                    if let Some(exec_context) = thread.exec_context {
-                        match exec_context.trigger_context {
-                            TriggerContext::Account {
-                                data_hash: prior_data_hash,
-                            } => {
-                                require!(
-                                    data_hash.ne(&prior_data_hash),
-                                    ClockworkError::TriggerConditionFailed
-                                )
-                            }
-                            _ => return Err(ClockworkError::InvalidThreadState.into()),
-                        }
-                    }
+            let account_info = remaining_accounts
+                .iter()
+                .find(|account_info| address.eq(account_info.key))
+                .ok_or(ClockworkError::TriggerConditionFailed)?;
 
-                    // Set a new exec context with the new data hash and slot number.
-                    thread.exec_context = Some(ExecContext {
-                        exec_index: 0,
-                        execs_since_reimbursement: 0,
-                        execs_since_slot: 0,
-                        last_exec_at: clock.slot,
-                        trigger_context: TriggerContext::Account { data_hash },
-                    })
-                }
-            }
+            // Compute the data hash of this account.
+            let data_hash = hash_account_data(&account_info.try_borrow_data()?, offset, size);
+            verify_account_hash(data_hash, trigger_context)
         }
-        Trigger::Cron {
-            schedule,
-            skippable,
+        Trigger::AccountComponents {
+            address,
+            components,
         } => {
-            // Get the reference timestamp for calculating the thread's scheduled target timestamp.
-            let reference_timestamp = match thread.exec_context.clone() {
-                None => thread.created_at.unix_timestamp,
-                Some(exec_context) => match exec_context.trigger_context {
-                    TriggerContext::Cron { started_at } => started_at,
-                    _ => return Err(ClockworkError::InvalidThreadState.into()),
-                },
-            };
-
-            // Verify the current timestamp is greater than or equal to the threshold timestamp.
-            let threshold_timestamp = next_timestamp(reference_timestamp, schedule.clone())
+            // Verify proof that the account has been updated.
+            let account_info = remaining_accounts
+                .iter()
+                .find(|account_info| address.eq(account_info.key))
                 .ok_or(ClockworkError::TriggerConditionFailed)?;
-            require!(
-                clock.unix_timestamp.ge(&threshold_timestamp),
-                ClockworkError::TriggerConditionFailed
-            );
 
-            // If the schedule is marked as skippable, set the started_at of the exec context to be the current timestamp.
-            // Otherwise, the exec context must iterate through each scheduled kickoff moment.
-            let started_at = if skippable {
-                clock.unix_timestamp
-            } else {
-                threshold_timestamp
-            };
-
-            // Set the exec context.
-            thread.exec_context = Some(ExecContext {
-                exec_index: 0,
-                execs_since_reimbursement: 0,
-                execs_since_slot: 0,
-                last_exec_at: clock.slot,
-                trigger_context: TriggerContext::Cron { started_at },
-            });
+            // Compute the hash of the account's watched components.
+            let data_hash = hash_account_components(
+                &account_info.try_borrow_data()?,
+                account_info.lamports(),
+                account_info.owner,
+                &components,
+            );
+            verify_account_hash(data_hash, trigger_context)
         }
+        Trigger::Signal { .. } => match trigger_context {
+            // Disarm the trigger, so each signal kicks off the thread exactly once.
+            Some(TriggerContext::Signal {
+                armed: true,
+                payload,
+            }) => Ok((
+                TriggerContext::Signal {
+                    armed: false,
+                    payload,
+                },
+                true,
+            )),
+            _ => Err(ClockworkError::TriggerConditionFailed.into()),
+        },
+        Trigger::Cron {
+            schedule,
+            skippable,
+        } => verify_cron_trigger(
+            schedule,
+            skippable,
+            None,
+            trigger_context,
+            created_at,
+            clock,
+        ),
+        Trigger::CronTz {
+            schedule,
+            skippable,
+            timezone,
+        } => verify_cron_trigger(
+            schedule,
+            skippable,
+            Some(timezone),
+            trigger_context,
+            created_at,
+            clock,
+        ),
         Trigger::Now => {
-            // Set the exec context.
             require!(
-                thread.exec_context.is_none(),
+                trigger_context.is_none(),
                 ClockworkError::InvalidThreadState
             );
-            thread.exec_context = Some(ExecContext {
-                exec_index: 0,
-                execs_since_reimbursement: 0,
-                execs_since_slot: 0,
-                last_exec_at: clock.slot,
-                trigger_context: TriggerContext::Now,
-            });
+            Ok((TriggerContext::Now, true))
         }
         Trigger::Slot { slot } => {
             require!(clock.slot.ge(&slot), ClockworkError::TriggerConditionFailed);
-            thread.exec_context = Some(ExecContext {
-                exec_index: 0,
-                execs_since_reimbursement: 0,
-                execs_since_slot: 0,
-                last_exec_at: clock.slot,
-                trigger_context: TriggerContext::Slot { started_at: slot },
-            });
+            Ok((TriggerContext::Slot { started_at: slot }, true))
         }
         Trigger::Epoch { epoch } => {
             require!(
                 clock.epoch.ge(&epoch),
                 ClockworkError::TriggerConditionFailed
             );
-            thread.exec_context = Some(ExecContext {
-                exec_index: 0,
-                execs_since_reimbursement: 0,
-                execs_since_slot: 0,
-                last_exec_at: clock.slot,
-                trigger_context: TriggerContext::Epoch { started_at: epoch },
-            })
+            Ok((TriggerContext::Epoch { started_at: epoch }, true))
         }
         Trigger::Timestamp { unix_ts } => {
             require!(
                 clock.unix_timestamp.ge(&unix_ts),
                 ClockworkError::TriggerConditionFailed
             );
-            thread.exec_context = Some(ExecContext {
-                exec_index: 0,
-                execs_since_reimbursement: 0,
-                execs_since_slot: 0,
-                last_exec_at: clock.slot,
-                trigger_context: TriggerContext::Timestamp {
+            Ok((
+                TriggerContext::Timestamp {
                     started_at: unix_ts,
                 },
-            })
+                true,
+            ))
         }
         Trigger::Pyth {
+            price_feed,
+            equality,
+            limit,
+        } => {
+            // Pyth triggers compare the price itself, with the default staleness threshold.
+            let trigger = Trigger::PythPrice {
+                price_feed,
+                equality,
+                limit,
+                mode: PythMode::Absolute,
+                max_staleness: DEFAULT_PYTH_STALENESS,
+                max_confidence_bps: None,
+            };
+            verify_trigger(
+                &trigger,
+                trigger_context,
+                created_at,
+                clock,
+                remaining_accounts,
+            )
+        }
+        Trigger::PythPrice {
             price_feed: price_feed_pubkey,
             equality,
             limit,
+            mode,
+            max_staleness,
+            max_confidence_bps,
         } => {
             // Verify price limit has been reached.
-            match ctx.remaining_accounts.first() {
-                None => {
-                    return Err(ClockworkError::TriggerConditionFailed.into());
-                }
-                Some(account_info) => {
-                    require!(
-                        price_feed_pubkey.eq(account_info.key),
-                        ClockworkError::TriggerConditionFailed
-                    );
-                    const STALENESS_THRESHOLD: u64 = 60; // staleness threshold in seconds
-                    let price_feed = load_price_feed_from_account_info(account_info).unwrap();
-                    let current_timestamp = Clock::get()?.unix_timestamp;
-                    let current_price = price_feed
-                        .get_price_no_older_than(current_timestamp, STALENESS_THRESHOLD)
-                        .unwrap();
-                    match equality {
-                        Equality::GreaterThanOrEqual => {
-                            require!(
-                                current_price.price.ge(&limit),
-                                ClockworkError::TriggerConditionFailed
-                            );
-                            thread.exec_context = Some(ExecContext {
-                                exec_index: 0,
-                                execs_since_reimbursement: 0,
-                                execs_since_slot: 0,
-                                last_exec_at: clock.slot,
-                                trigger_context: TriggerContext::Pyth {
-                                    price: current_price.price,
-                                },
-                            });
-                        }
-                        Equality::LessThanOrEqual => {
-                            require!(
-                                current_price.price.le(&limit),
-                                ClockworkError::TriggerConditionFailed
-                            );
-                            thread.exec_context = Some(ExecContext {
-                                exec_index: 0,
-                                execs_since_reimbursement: 0,
-                                execs_since_slot: 0,
-                                last_exec_at: clock.slot,
-                                trigger_context: TriggerContext::Pyth {
-                                    price: current_price.price,
-                                },
-                            });
-                        }
+            // A missing, malformed or stale price feed fails the trigger condition.
+            let account_info = remaining_accounts
+                .iter()
+                .find(|account_info| price_feed_pubkey.eq(account_info.key))
+                .ok_or(ClockworkError::TriggerConditionFailed)?;
+            let current_price = load_price_feed_from_account_info(account_info)
+                .map_err(|_| ClockworkError::TriggerConditionFailed)?
+                .get_price_no_older_than(clock.unix_timestamp, max_staleness)
+                .ok_or(ClockworkError::TriggerConditionFailed)?;
+            let prior_price = match trigger_context {
+                None => None,
+                Some(TriggerContext::Pyth { price }) => Some(price),
+                Some(_) => return Err(ClockworkError::InvalidThreadState.into()),
+            };
+            let is_active = verify_price_trigger(
+                current_price.price,
+                current_price.conf,
+                prior_price,
+                equality,
+                limit,
+                mode,
+                max_confidence_bps,
+            )?;
+            Ok((
+                TriggerContext::Pyth {
+                    price: current_price.price,
+                },
+                is_active,
+            ))
+        }
+        Trigger::Any { triggers } => {
+            let prior_contexts = match trigger_context {
+                None => TriggerContexts::default(),
+                Some(TriggerContext::Any { contexts }) => contexts,
+                Some(_) => return Err(ClockworkError::InvalidThreadState.into()),
+            };
+
+            // Verify at least one child trigger is active or has updated its context.
+            // Children which fail verification keep their prior context.
+            let mut is_active = false;
+            let mut is_updated = false;
+            let mut contexts = vec![];
+            for (i, trigger) in triggers.iter().enumerate() {
+                let prior_context = prior_contexts.get(i).cloned().flatten();
+                match verify_trigger(
+                    trigger,
+                    prior_context.clone(),
+                    created_at,
+                    clock,
+                    remaining_accounts,
+                ) {
+                    Ok((context, is_child_active)) => {
+                        is_active = is_active || is_child_active;
+                        is_updated = true;
+                        contexts.push(Some(context));
                     }
+                    Err(_) => contexts.push(prior_context),
                 }
             }
+            require!(is_updated, ClockworkError::TriggerConditionFailed);
+
+            Ok((
+                TriggerContext::Any {
+                    contexts: TriggerContexts(contexts),
+                },
+                is_active,
+            ))
+        }
+        Trigger::All { triggers } => {
+            let prior_contexts = match trigger_context {
+                None => TriggerContexts::default(),
+                Some(TriggerContext::All { contexts }) => contexts,
+                Some(_) => return Err(ClockworkError::InvalidThreadState.into()),
+            };
+
+            // Verify every child trigger is active or has updated its context.
+            let mut is_active = true;
+            let mut results = vec![];
+            for (i, trigger) in triggers.iter().enumerate() {
+                let prior_context = prior_contexts.get(i).cloned().flatten();
+                let (context, is_child_active) = verify_trigger(
+                    trigger,
+                    prior_context.clone(),
+                    created_at,
+                    clock,
+                    remaining_accounts,
+                )?;
+                is_active = is_active && is_child_active;
+                results.push((prior_context, context, is_child_active));
+            }
+
+            // Unless every child is active, active children keep their prior context,
+            // so the moment they are waiting for is not consumed. Inactive children still record their baselines and re-arms.
+            let contexts = results
+                .into_iter()
+                .map(|(prior_context, context, is_child_active)| {
+                    if is_active || !is_child_active {
+                        Some(context)
+                    } else {
+                        prior_context
+                    }
+                })
+                .collect();
+
+            Ok((
+                TriggerContext::All {
+                    contexts: TriggerContexts(contexts),
+                },
+                is_active,
+            ))
+        }
+        Trigger::AccountValue {
+            address,
+            offset,
+            equality,
+            threshold,
+        } => {
+            // Read the monitored value from the account data.
+            let account_info = remaining_accounts
+                .iter()
+                .find(|account_info| address.eq(account_info.key))
+                .ok_or(ClockworkError::TriggerConditionFailed)?;
+            let value = threshold
+                .read(&account_info.try_borrow_data().unwrap(), offset)
+                .ok_or(ClockworkError::TriggerConditionFailed)?;
+
+            // Verify the value satisfies the condition, or re-arms a crossing operator.
+            let prior_value = match trigger_context {
+                None => None,
+                Some(TriggerContext::AccountValue { value }) => Some(value),
+                Some(_) => return Err(ClockworkError::InvalidThreadState.into()),
+            };
+            let is_active = equality.is_satisfied(&value, &threshold, prior_value.as_ref());
+            require!(
+                is_active || equality.is_rearmed(&value, &threshold, prior_value.as_ref()),
+                ClockworkError::TriggerConditionFailed
+            );
+
+            Ok((TriggerContext::AccountValue { value }, is_active))
+        }
+        Trigger::SlotInterval { start, interval } => {
+            let last_boundary = match trigger_context {
+                None => None,
+                Some(TriggerContext::SlotInterval { started_at }) => Some(started_at),
+                Some(_) => return Err(ClockworkError::InvalidThreadState.into()),
+            };
+            let started_at = latest_boundary(start, interval, last_boundary, clock.slot)?;
+            Ok((TriggerContext::SlotInterval { started_at }, true))
+        }
+        Trigger::EpochInterval { interval, offset } => {
+            let last_boundary = match trigger_context {
+                None => None,
+                Some(TriggerContext::EpochInterval { started_at }) => Some(started_at),
+                Some(_) => return Err(ClockworkError::InvalidThreadState.into()),
+            };
+            let started_at = latest_boundary(offset, interval, last_boundary, clock.epoch)?;
+            Ok((TriggerContext::EpochInterval { started_at }, true))
+        }
+        Trigger::ThreadCompleted { thread } => {
+            // Read the upstream thread's run counter.
+            let account_info = remaining_accounts
+                .iter()
+                .find(|account_info| thread.eq(account_info.key))
+                .ok_or(ClockworkError::TriggerConditionFailed)?;
+            require!(
+                account_info.owner.eq(&crate::ID),
+                ClockworkError::TriggerConditionFailed
+            );
+            let upstream_thread =
+                Thread::try_deserialize(&mut account_info.try_borrow_data()?.as_ref())?;
+            let runs_completed = upstream_thread.exec_counters.runs_completed;
+
+            // Verify the upstream thread has completed a run this thread has not been kicked off for.
+            // Runs are consumed one at a time, so this thread runs exactly once per upstream run.
+            // The first kickoff only records the upstream thread's completed runs as a baseline,
+            // so runs completed before the trigger was set up do not kick off the thread.
+            let run = match trigger_context {
+                None => {
+                    return Ok((
+                        TriggerContext::ThreadCompleted {
+                            run: runs_completed,
+                        },
+                        false,
+                    ))
+                }
+                Some(TriggerContext::ThreadCompleted { run }) => run.checked_add(1).unwrap(),
+                Some(_) => return Err(ClockworkError::InvalidThreadState.into()),
+            };
+            require!(
+                run.gt(&0) && runs_completed.ge(&run),
+                ClockworkError::TriggerConditionFailed
+            );
+
+            Ok((TriggerContext::ThreadCompleted { run }, true))
+        }
+        Trigger::Oracle {
+            source,
+            price_feed: price_feed_pubkey,
+            decimals,
+            equality,
+            limit,
+            mode,
+            max_staleness,
+            max_confidence_bps,
+        } => {
+            // Load a fresh price, scaled to the trigger's decimals.
+            let account_info = remaining_accounts
+                .iter()
+                .find(|account_info| price_feed_pubkey.eq(account_info.key))
+                .ok_or(ClockworkError::TriggerConditionFailed)?;
+            let current_price = source
+                .load_price(&account_info.try_borrow_data()?)
+                .filter(|price| price.is_fresh(clock.unix_timestamp, max_staleness))
+                .ok_or(ClockworkError::TriggerConditionFailed)?;
+            let (price, conf) = current_price
+                .scaled(decimals)
+                .ok_or(ClockworkError::TriggerConditionFailed)?;
+            let prior_price = match trigger_context {
+                None => None,
+                Some(TriggerContext::Oracle { price }) => Some(price),
+                Some(_) => return Err(ClockworkError::InvalidThreadState.into()),
+            };
+            let is_active = verify_price_trigger(
+                price,
+                conf,
+                prior_price,
+                equality,
+                limit,
+                mode,
+                max_confidence_bps,
+            )?;
+            Ok((TriggerContext::Oracle { price }, is_active))
+        }
+        Trigger::TokenBalance {
+            token_account,
+            equality,
+            amount: threshold,
+        } => {
+            // Read the balance of the monitored token account.
+            let account_info = remaining_accounts
+                .iter()
+                .find(|account_info| token_account.eq(account_info.key))
+                .ok_or(ClockworkError::TriggerConditionFailed)?;
+            require!(
+                account_info.owner.eq(&TOKEN_PROGRAM_ID)
+                    || account_info.owner.eq(&TOKEN_2022_PROGRAM_ID),
+                ClockworkError::TriggerConditionFailed
+            );
+            let amount = read_token_amount(&account_info.try_borrow_data()?)
+                .ok_or(ClockworkError::TriggerConditionFailed)?;
+
+            // Verify the balance satisfies the condition, or re-arms a crossing operator.
+            let prior_amount = match trigger_context {
+                None => None,
+                Some(TriggerContext::TokenBalance { amount }) => Some(amount),
+                Some(_) => return Err(ClockworkError::InvalidThreadState.into()),
+            };
+            let is_active = equality.is_satisfied(&amount, &threshold, prior_amount.as_ref());
+            require!(
+                is_active || equality.is_rearmed(&amount, &threshold, prior_amount.as_ref()),
+                ClockworkError::TriggerConditionFailed
+            );
+
+            Ok((TriggerContext::TokenBalance { amount }, is_active))
         }
     }
+}
 
-    // If we make it here, the trigger is active. Update the next instruction and be done.
-    if let Some(kickoff_instruction) = thread.instructions.first() {
-        thread.next_instruction = Some(kickoff_instruction.clone());
+/// Verifies a cron schedule's next moment has been reached, and returns the new trigger context.
+/// Schedules without a timezone are evaluated in UTC.
+fn verify_cron_trigger(
+    schedule: String,
+    skippable: bool,
+    timezone: Option<String>,
+    trigger_context: Option<TriggerContext>,
+    created_at: i64,
+    clock: &Clock,
+) -> Result<(TriggerContext, bool)> {
+    // Get the reference timestamp for calculating the thread's scheduled target timestamp.
+    let reference_timestamp = match trigger_context {
+        None => created_at,
+        Some(trigger_context) => match trigger_context {
+            TriggerContext::Cron { started_at } => started_at,
+            _ => return Err(ClockworkError::InvalidThreadState.into()),
+        },
+    };
+
+    // Verify the current timestamp is greater than or equal to the threshold timestamp.
+    let threshold_timestamp = next_timestamp(reference_timestamp, schedule, timezone)
+        .ok_or(ClockworkError::TriggerConditionFailed)?;
+    require!(
+        clock.unix_timestamp.ge(&threshold_timestamp),
+        ClockworkError::TriggerConditionFailed
+    );
+
+    // If the schedule is marked as skippable, set the started_at of the exec context to be the current timestamp.
+    // Otherwise, the exec context must iterate through each scheduled kickoff moment.
+    let started_at = if skippable {
+        clock.unix_timestamp
+    } else {
+        threshold_timestamp
+    };
+
+    Ok((TriggerContext::Cron { started_at }, true))
+}
+
+/// Verifies an account hash is different than the prior one, and returns the new trigger context.
+/// A legacy data hash was computed with an unspecified hasher and can't be compared,
+/// so the new hash is recorded without kicking off the thread.
+fn verify_account_hash(
+    data_hash: [u8; 32],
+    trigger_context: Option<TriggerContext>,
+) -> Result<(TriggerContext, bool)> {
+    match trigger_context {
+        None => {}
+        Some(TriggerContext::Account { .. }) => {
+            return Ok((TriggerContext::AccountHash { data_hash }, false))
+        }
+        Some(TriggerContext::AccountHash {
+            data_hash: prior_data_hash,
+        }) => require!(
+            data_hash.ne(&prior_data_hash),
+            ClockworkError::TriggerConditionFailed
+        ),
+        Some(_) => return Err(ClockworkError::InvalidThreadState.into()),
     }
+    Ok((TriggerContext::AccountHash { data_hash }, true))
+}
 
-    // Realloc the thread account
-    thread.realloc()?;
+/// Verifies a price trigger's condition has been reached, and returns whether the trigger is active.
+fn verify_price_trigger(
+    price: i64,
+    conf: u64,
+    prior_price: Option<i64>,
+    equality: Equality,
+    limit: i64,
+    mode: PythMode,
+    max_confidence_bps: Option<u64>,
+) -> Result<bool> {
+    require!(
+        is_price_confident(price, conf, max_confidence_bps),
+        ClockworkError::TriggerConditionFailed
+    );
 
-    // Reimburse signatory for transaction fee.
-    **thread.to_account_info().try_borrow_mut_lamports()? = thread
-        .to_account_info()
-        .lamports()
-        .checked_sub(TRANSACTION_BASE_FEE_REIMBURSEMENT)
-        .unwrap();
-    **signatory.to_account_info().try_borrow_mut_lamports()? = signatory
-        .to_account_info()
-        .lamports()
-        .checked_add(TRANSACTION_BASE_FEE_REIMBURSEMENT)
-        .unwrap();
+    // In relative mode, the first price observed is recorded as the baseline to measure moves from.
+    let value = match mode.value(price, prior_price) {
+        None => return Ok(false),
+        Some(value) => value,
+    };
 
-    Ok(())
+    // Relative moves are measured from the last run, so they have no prior value to cross from.
+    let prior_value = match mode {
+        PythMode::Absolute => prior_price,
+        PythMode::Relative => None,
+    };
+    let is_active = equality.is_satisfied(&value, &limit, prior_value.as_ref());
+    require!(
+        is_active || equality.is_rearmed(&value, &limit, prior_value.as_ref()),
+        ClockworkError::TriggerConditionFailed
+    );
+    Ok(is_active)
 }
 
-fn next_timestamp(after: i64, schedule: String) -> Option<i64> {
+/// Verifies the boundary following the last recorded one has been reached,
+/// and returns the latest boundary at or before the current slot or epoch.
+fn latest_boundary(
+    start: u64,
+    interval: u64,
+    last_boundary: Option<u64>,
+    current: u64,
+) -> Result<u64> {
+    require!(interval.gt(&0), ClockworkError::TriggerConditionFailed);
+    let threshold = match last_boundary {
+        None => start,
+        Some(last_boundary) => last_boundary
+            .checked_add(interval)
+            .ok_or(ClockworkError::TriggerConditionFailed)?,
+    };
+    require!(
+        current.ge(&threshold),
+        ClockworkError::TriggerConditionFailed
+    );
+    Ok(threshold + (current - threshold) / interval * interval)
+}
+
+fn next_timestamp(after: i64, schedule: String, timezone: Option<String>) -> Option<i64> {
+    let timezone = match timezone {
+        None => Tz::UTC,
+        Some(timezone) => Tz::from_str(&timezone).ok()?,
+    };
     Schedule::from_str(&schedule)
         .unwrap()
-        .next_after(&DateTime::<Utc>::from_naive_utc_and_offset(
-            NaiveDateTime::from_timestamp_opt(after, 0).unwrap(),
-            Utc,
-        ))
+        .next_after(&timezone.timestamp_opt(after, 0).single()?)
         .take()
         .map(|datetime| datetime.timestamp())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_latest_boundary() {
+        // The first boundary is the start.
+        assert!(latest_boundary(100, 10, None, 99).is_err());
+        assert_eq!(100, latest_boundary(100, 10, None, 100).unwrap());
+        assert_eq!(120, latest_boundary(100, 10, None, 125).unwrap());
+
+        // The next boundary follows the last recorded one, and missed boundaries are skipped.
+        assert!(latest_boundary(100, 10, Some(120), 129).is_err());
+        assert_eq!(130, latest_boundary(100, 10, Some(120), 130).unwrap());
+        assert_eq!(150, latest_boundary(100, 10, Some(120), 155).unwrap());
+    }
+
+    #[test]
+    fn test_latest_boundary_invalid() {
+        assert!(latest_boundary(100, 0, None, 100).is_err());
+        assert!(latest_boundary(0, 10, Some(u64::MAX), u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_all_trigger_keeps_active_child_contexts() {
+        let clock = Clock {
+            unix_timestamp: 100,
+            ..Clock::default()
+        };
+        let address = Pubkey::new_unique();
+        let trigger = Trigger::All {
+            triggers: Triggers(vec![
+                Trigger::Cron {
+                    schedule: "* * * * * * *".into(),
+                    skippable: false,
+                },
+                Trigger::AccountValue {
+                    address,
+                    offset: 0,
+                    equality: Equality::CrossesUp,
+                    threshold: DataValue::U64(10),
+                },
+            ]),
+        };
+        let verify = |value: u64, trigger_context: TriggerContext| {
+            let mut lamports = 0;
+            let mut data = value.to_le_bytes();
+            let owner = Pubkey::default();
+            let account_info = AccountInfo::new(
+                &address,
+                false,
+                false,
+                &mut lamports,
+                &mut data,
+                &owner,
+                false,
+                0,
+            );
+            verify_trigger(&trigger, Some(trigger_context), 0, &clock, &[account_info]).unwrap()
+        };
+        let prior_context = TriggerContext::All {
+            contexts: TriggerContexts(vec![
+                Some(TriggerContext::Cron { started_at: 50 }),
+                Some(TriggerContext::AccountValue {
+                    value: DataValue::U64(20),
+                }),
+            ]),
+        };
+
+        // The value falls below the threshold, re-arming the account child while the cron child is due.
+        // The cron moment is not consumed, but the re-arm is recorded.
+        let (context, is_active) = verify(5, prior_context);
+        assert!(!is_active);
+        assert_eq!(
+            context,
+            TriggerContext::All {
+                contexts: TriggerContexts(vec![
+                    Some(TriggerContext::Cron { started_at: 50 }),
+                    Some(TriggerContext::AccountValue {
+                        value: DataValue::U64(5),
+                    }),
+                ]),
+            }
+        );
+
+        // Once the value crosses the threshold, every child is active and all of their contexts are recorded.
+        let (context, is_active) = verify(15, context);
+        assert!(is_active);
+        assert_eq!(
+            context,
+            TriggerContext::All {
+                contexts: TriggerContexts(vec![
+                    Some(TriggerContext::Cron { started_at: 51 }),
+                    Some(TriggerContext::AccountValue {
+                        value: DataValue::U64(15),
+                    }),
+                ]),
+            }
+        );
+    }
+}
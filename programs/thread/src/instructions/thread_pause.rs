@@ -1,9 +1,12 @@
-use {crate::state::*, anchor_lang::prelude::*};
+use {
+    crate::{errors::*, state::*},
+    anchor_lang::prelude::*,
+};
 
 /// Accounts required by the `thread_delete` instruction.
 #[derive(Accounts)]
 pub struct ThreadPause<'info> {
-    /// The authority (owner) of the thread.
+    /// The authority (owner) of the thread, or a delegate with permission to pause it.
     #[account()]
     pub authority: Signer<'info>,
 
@@ -16,7 +19,7 @@ pub struct ThreadPause<'info> {
             thread.id.as_slice(),
         ],
         bump = thread.bump,
-        has_one = authority
+        constraint = thread.is_authorized(&authority.key(), ThreadPermission::Pause) @ ClockworkError::UnauthorizedSigner
     )]
     pub thread: Account<'info, Thread>,
 }
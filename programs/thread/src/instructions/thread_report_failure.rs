@@ -0,0 +1,187 @@
+use anchor_lang::prelude::*;
+use clockwork_network_program::state::{Pool, Worker, WorkerAccount};
+
+use crate::{errors::ClockworkError, state::*};
+
+use super::{POOL_ID, TRANSACTION_BASE_FEE_REIMBURSEMENT};
+
+/// Number of slots a thread must go without executing before a worker may report its next instruction as failed.
+pub const FAILURE_REPORT_DELAY: u64 = 4;
+
+/// Accounts required by the `thread_report_failure` instruction.
+#[derive(Accounts)]
+pub struct ThreadReportFailure<'info> {
+    /// The active worker pool.
+    #[account(address = Pool::pubkey(POOL_ID))]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The signatory.
+    #[account(mut)]
+    pub signatory: Signer<'info>,
+
+    /// The thread whose next instruction failed.
+    #[account(
+        mut,
+        seeds = [
+            SEED_THREAD,
+            thread.authority.as_ref(),
+            thread.id.as_slice(),
+        ],
+        bump = thread.bump,
+        constraint = !thread.paused @ ClockworkError::ThreadPaused
+    )]
+    pub thread: Box<Account<'info, Thread>>,
+
+    /// The worker reporting the failure. It must be in the pool.
+    #[account(
+        address = worker.pubkey(),
+        has_one = signatory,
+        constraint = pool.workers.contains(&worker.key()) @ ClockworkError::UnauthorizedSigner
+    )]
+    pub worker: Account<'info, Worker>,
+}
+
+pub fn handler(ctx: Context<ThreadReportFailure>, error_code: Option<u32>) -> Result<()> {
+    // Get accounts
+    let clock = Clock::get().unwrap();
+    let signatory = &mut ctx.accounts.signatory;
+    let thread = &mut ctx.accounts.thread;
+
+    // Only accept reports against a thread which is due and has not been executed recently.
+    verify_failure_report(
+        &thread.next_instruction,
+        &thread.exec_context,
+        thread.exec_counters.last_failure_at,
+        clock.slot,
+    )?;
+
+    // Record the failure.
+    let mut exec_context = thread.exec_context.clone().unwrap();
+    let mut exec_counters = ExecCounters {
+        consecutive_failures: thread
+            .exec_counters
+            .consecutive_failures
+            .checked_add(1)
+            .unwrap(),
+        last_error_code: error_code,
+        last_failure_at: Some(clock.slot),
+        ..thread.exec_counters.clone()
+    };
+
+    // If the thread has reached its failure limit, apply its failure policy.
+    let max_consecutive_failures = thread.failure_policy.max_consecutive_failures;
+    if max_consecutive_failures.map_or(false, |max| exec_counters.consecutive_failures.ge(&max)) {
+        match thread.failure_policy.action {
+            FailureAction::Pause => {
+                thread.paused = true;
+            }
+            FailureAction::SkipInstruction => {
+                // Move on to the next dynamic instruction, or the next instruction in the instruction set.
+                if !thread.dynamic_instructions.is_empty() {
+                    thread.next_instruction = Some(thread.dynamic_instructions.remove(0));
+                } else if let Some(ix) = thread
+                    .instructions
+                    .get((exec_context.exec_index + 1) as usize)
+                {
+                    thread.next_instruction = Some(ix.clone());
+                    exec_context.exec_index += 1;
+                } else {
+                    thread.next_instruction = None;
+                    exec_counters.runs_completed =
+                        exec_counters.runs_completed.checked_add(1).unwrap();
+                }
+                exec_counters.consecutive_failures = 0;
+            }
+            FailureAction::EndRun => {
+                thread.next_instruction = None;
+                thread.dynamic_instructions = vec![];
+                exec_counters.consecutive_failures = 0;
+            }
+        }
+    }
+    thread.exec_context = Some(exec_context);
+    thread.exec_counters = exec_counters;
+
+    // If the thread has no failure limit, the report cannot affect its execution.
+    if max_consecutive_failures.is_none() {
+        return Ok(());
+    }
+
+    // Reimburse signatory for transaction fee.
+    **thread.to_account_info().try_borrow_mut_lamports()? = thread
+        .to_account_info()
+        .lamports()
+        .checked_sub(TRANSACTION_BASE_FEE_REIMBURSEMENT)
+        .unwrap();
+    **signatory.to_account_info().try_borrow_mut_lamports()? = signatory
+        .to_account_info()
+        .lamports()
+        .checked_add(TRANSACTION_BASE_FEE_REIMBURSEMENT)
+        .unwrap();
+
+    Ok(())
+}
+
+fn verify_failure_report(
+    next_instruction: &Option<SerializableInstruction>,
+    exec_context: &Option<ExecContext>,
+    last_failure_at: Option<u64>,
+    slot: u64,
+) -> Result<()> {
+    // The thread must have an instruction waiting to be executed.
+    let exec_context = match exec_context {
+        Some(exec_context) if next_instruction.is_some() => exec_context,
+        _ => return Err(ClockworkError::InvalidThreadState.into()),
+    };
+
+    // The thread must not have been executed within the report delay.
+    require!(
+        exec_context.last_exec_at < slot.saturating_sub(FAILURE_REPORT_DELAY),
+        ClockworkError::InvalidThreadState
+    );
+
+    // Only accept one report per slot.
+    require!(
+        last_failure_at.ne(&Some(slot)),
+        ClockworkError::FailureAlreadyReported
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use anchor_lang::solana_program::instruction::Instruction;
+
+    fn exec_context(last_exec_at: u64) -> Option<ExecContext> {
+        Some(ExecContext {
+            exec_index: 0,
+            execs_since_reimbursement: 0,
+            execs_since_slot: 0,
+            last_exec_at,
+            trigger_context: TriggerContext::Now,
+        })
+    }
+
+    #[test]
+    fn test_verify_failure_report() {
+        let next_instruction = Some(Instruction::new_with_bytes(crate::ID, &[], vec![]).into());
+        assert!(verify_failure_report(&next_instruction, &exec_context(100), None, 110).is_ok());
+
+        // A second report in the same slot is rejected.
+        assert!(
+            verify_failure_report(&next_instruction, &exec_context(100), Some(110), 110).is_err()
+        );
+
+        // A thread executed within the report delay is not yet considered failed.
+        assert!(verify_failure_report(&next_instruction, &exec_context(108), None, 110).is_err());
+    }
+
+    #[test]
+    fn test_verify_failure_report_idle_thread() {
+        // An idle thread has no instruction waiting to be executed.
+        assert!(verify_failure_report(&None, &exec_context(100), None, 110).is_err());
+        assert!(verify_failure_report(&None, &None, None, 110).is_err());
+    }
+}
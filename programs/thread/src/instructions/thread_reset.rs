@@ -1,4 +1,7 @@
-use {crate::state::*, anchor_lang::prelude::*};
+use {
+    crate::{errors::*, state::*},
+    anchor_lang::prelude::*,
+};
 
 /// Accounts required by the `thread_reset` instruction.
 #[derive(Accounts)]
@@ -16,7 +19,7 @@ pub struct ThreadReset<'info> {
             thread.id.as_slice(),
         ],
         bump = thread.bump,
-        has_one = authority
+        constraint = thread.current_authority().eq(&authority.key()) @ ClockworkError::UnauthorizedSigner
     )]
     pub thread: Account<'info, Thread>,
 }
@@ -27,6 +30,7 @@ pub fn handler(ctx: Context<ThreadReset>) -> Result<()> {
 
     // Full reset the thread state.
     thread.next_instruction = None;
+    thread.dynamic_instructions = vec![];
     thread.exec_context = None;
     thread.created_at = Clock::get().unwrap().into();
 
@@ -1,9 +1,12 @@
-use {crate::state::*, anchor_lang::prelude::*};
+use {
+    crate::{errors::*, state::*},
+    anchor_lang::prelude::*,
+};
 
 /// Accounts required by the `thread_resume` instruction.
 #[derive(Accounts)]
 pub struct ThreadResume<'info> {
-    /// The authority (owner) of the thread.
+    /// The authority (owner) of the thread, or a delegate with permission to resume it.
     #[account()]
     pub authority: Signer<'info>,
 
@@ -16,7 +19,7 @@ pub struct ThreadResume<'info> {
             thread.id.as_slice(),
         ],
         bump = thread.bump,
-        has_one = authority
+        constraint = thread.is_authorized(&authority.key(), ThreadPermission::Pause) @ ClockworkError::UnauthorizedSigner
     )]
     pub thread: Account<'info, Thread>,
 }
@@ -28,8 +31,11 @@ pub fn handler(ctx: Context<ThreadResume>) -> Result<()> {
     // Resume the thread
     thread.paused = false;
 
+    // Reset the failure count, so the thread can be retried from a clean slate.
+    thread.exec_counters.consecutive_failures = 0;
+
     // Update the exec context
-    match thread.exec_context {
+    match thread.exec_context.clone() {
         None => {}
         Some(exec_context) => {
             match exec_context.trigger_context {
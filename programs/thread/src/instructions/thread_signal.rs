@@ -0,0 +1,114 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::system_program,
+    system_program::{transfer, Transfer},
+};
+use clockwork_utils::thread::Trigger;
+
+use crate::{errors::*, state::*};
+
+/// Accounts required by the `thread_signal` instruction.
+#[derive(Accounts)]
+#[instruction(payload: Option<Vec<u8>>)]
+pub struct ThreadSignal<'info> {
+    /// The payer for any rent required to store the payload.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The signer of the signal. It must be one of the trigger's allowed signers.
+    #[account()]
+    pub signer: Signer<'info>,
+
+    /// The Solana system program
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+
+    /// The thread to be signaled.
+    #[account(
+        mut,
+        seeds = [
+            SEED_THREAD,
+            thread.authority.as_ref(),
+            thread.id.as_slice(),
+        ],
+        bump = thread.bump,
+    )]
+    pub thread: Account<'info, Thread>,
+}
+
+pub fn handler(ctx: Context<ThreadSignal>, payload: Option<Vec<u8>>) -> Result<()> {
+    // Get accounts
+    let clock = Clock::get().unwrap();
+    let payer = &ctx.accounts.payer;
+    let signer = &ctx.accounts.signer;
+    let system_program = &ctx.accounts.system_program;
+    let thread = &mut ctx.accounts.thread;
+
+    // Verify the thread has a signal trigger and the signer is allowed to signal it.
+    match &thread.trigger {
+        Trigger::Signal { signers } => require!(
+            signers.contains(&signer.key()),
+            ClockworkError::UnauthorizedSigner
+        ),
+        _ => return Err(ClockworkError::InvalidTriggerVariant.into()),
+    }
+    require!(
+        payload
+            .as_ref()
+            .map_or(true, |payload| payload.len() <= MAX_SIGNAL_PAYLOAD_SIZE),
+        ClockworkError::SignalPayloadTooLarge
+    );
+
+    // Arm the trigger, so the next kickoff succeeds exactly once.
+    let trigger_context = TriggerContext::Signal {
+        armed: true,
+        payload,
+    };
+    match thread.exec_context.clone() {
+        Some(exec_context) => {
+            require!(
+                !matches!(
+                    exec_context.trigger_context,
+                    TriggerContext::Signal { armed: true, .. }
+                ),
+                ClockworkError::ThreadAlreadySignaled
+            );
+            thread.exec_context = Some(ExecContext {
+                trigger_context,
+                ..exec_context
+            });
+        }
+        None => {
+            thread.exec_context = Some(ExecContext {
+                exec_index: 0,
+                execs_since_reimbursement: 0,
+                execs_since_slot: 0,
+                last_exec_at: clock.slot,
+                trigger_context,
+            });
+        }
+    }
+
+    // Reallocate mem for the thread account.
+    thread.realloc()?;
+
+    // If lamports are required to maintain rent-exemption, pay them.
+    let data_len = thread.to_account_info().data_len();
+    let minimum_rent = Rent::get().unwrap().minimum_balance(data_len);
+    if minimum_rent > thread.to_account_info().lamports() {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: thread.to_account_info(),
+                },
+            ),
+            minimum_rent
+                .checked_sub(thread.to_account_info().lamports())
+                .unwrap(),
+        )?;
+    }
+
+    Ok(())
+}
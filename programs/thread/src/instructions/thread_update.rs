@@ -2,7 +2,7 @@ use crate::{errors::ClockworkError, state::*};
 
 use anchor_lang::{
     prelude::*,
-    solana_program::system_program,
+    solana_program::{pubkey::MAX_SEED_LEN, system_program},
     system_program::{transfer, Transfer},
 };
 
@@ -10,7 +10,7 @@ use anchor_lang::{
 #[derive(Accounts)]
 #[instruction(settings: ThreadSettings)]
 pub struct ThreadUpdate<'info> {
-    /// The authority (owner) of the thread.
+    /// The authority (owner) of the thread, or a delegate with permission to update the provided settings.
     #[account(mut)]
     pub authority: Signer<'info>,
 
@@ -27,7 +27,7 @@ pub struct ThreadUpdate<'info> {
                 thread.id.as_slice(),
             ],
             bump = thread.bump,
-            has_one = authority,
+            constraint = thread.current_authority().eq(&authority.key()) || thread.delegate(&authority.key()).is_some() @ ClockworkError::UnauthorizedSigner,
         )]
     pub thread: Account<'info, Thread>,
 }
@@ -38,14 +38,77 @@ pub fn handler(ctx: Context<ThreadUpdate>, settings: ThreadSettings) -> Result<(
     let thread = &mut ctx.accounts.thread;
     let system_program = &ctx.accounts.system_program;
 
+    // Delegates may only update the settings they have been granted permission for.
+    require!(
+        (settings.instructions.is_none() && settings.instruction_patches.is_none())
+            || thread.is_authorized(&authority.key(), ThreadPermission::EditInstructions),
+        ClockworkError::UnauthorizedSigner
+    );
+    require!(
+        settings.trigger.is_none()
+            || thread.is_authorized(&authority.key(), ThreadPermission::UpdateTrigger),
+        ClockworkError::UnauthorizedSigner
+    );
+    require!(
+        thread.current_authority().eq(&authority.key())
+            || (settings.failure_policy.is_none()
+                && settings.fee.is_none()
+                && settings.limits.is_none()
+                && settings.lookup_tables.is_none()
+                && settings.memory_size.is_none()
+                && settings.name.is_none()
+                && settings.priority_fee.is_none()
+                && settings.rate_limit.is_none()
+                && settings.signers.is_none()
+                && settings.spending_limits.is_none()),
+        ClockworkError::UnauthorizedSigner
+    );
+
     // Update the thread.
     if let Some(fee) = settings.fee {
         thread.fee = fee;
     }
 
+    // If provided, update the thread's failure policy.
+    if let Some(failure_policy) = settings.failure_policy {
+        thread.failure_policy = failure_policy;
+    }
+
     // If provided, update the thread's instruction set.
+    // The patches of the prior instruction set no longer apply, unless new patches are also provided.
     if let Some(instructions) = settings.instructions {
         thread.instructions = instructions;
+        thread.instruction_patches = vec![];
+    }
+
+    // If provided, update the thread's instruction patches.
+    if let Some(instruction_patches) = settings.instruction_patches {
+        thread.instruction_patches = instruction_patches;
+    }
+    thread.validate_instruction_patches()?;
+
+    // If provided, update the thread's limits.
+    if let Some(limits) = settings.limits {
+        thread.limits = limits;
+    }
+
+    // If provided, update the thread's address lookup tables.
+    if let Some(lookup_tables) = settings.lookup_tables {
+        thread.lookup_tables = lookup_tables;
+    }
+
+    // If provided, resize the thread's memory, keeping its existing contents.
+    if let Some(memory_size) = settings.memory_size {
+        require!(
+            memory_size as usize <= MAX_MEMORY_SIZE,
+            ClockworkError::InvalidMemorySize
+        );
+        thread.memory.resize(memory_size as usize, 0);
+    }
+
+    // If provided, update the thread's priority fee caps.
+    if let Some(priority_fee) = settings.priority_fee {
+        thread.priority_fee = priority_fee;
     }
 
     // If provided, update the rate limit.
@@ -53,6 +116,25 @@ pub fn handler(ctx: Context<ThreadUpdate>, settings: ThreadSettings) -> Result<(
         thread.rate_limit = rate_limit;
     }
 
+    // If provided, update the thread's derived signers.
+    if let Some(seeds) = settings.signers {
+        require!(
+            seeds.len() <= MAX_THREAD_SIGNERS
+                && seeds.iter().all(|seed| seed.len() <= MAX_SEED_LEN),
+            ClockworkError::InvalidThreadSigner
+        );
+        let thread_pubkey = thread.key();
+        thread.signers = seeds
+            .into_iter()
+            .map(|seed| ThreadSigner::new(thread_pubkey, seed))
+            .collect();
+    }
+
+    // If provided, update the thread's spending limits.
+    if let Some(spending_limits) = settings.spending_limits {
+        thread.spending_limits = spending_limits;
+    }
+
     // If provided, update the thread's trigger and reset the exec context.
     if let Some(trigger) = settings.trigger {
         // Require the thread is not in the middle of processing.
@@ -60,21 +142,15 @@ pub fn handler(ctx: Context<ThreadUpdate>, settings: ThreadSettings) -> Result<(
             std::mem::discriminant(&thread.trigger) == std::mem::discriminant(&trigger),
             ClockworkError::InvalidTriggerVariant
         );
+        validate_trigger(&trigger)?;
         thread.trigger = trigger.clone();
 
         // If the user updates an account trigger, the trigger context is no longer valid.
         // Here we reset the trigger context to zero to re-prime the trigger.
-        if thread.exec_context.is_some() {
+        if let Some(exec_context) = thread.exec_context.clone() {
             thread.exec_context = Some(ExecContext {
-                trigger_context: match trigger {
-                    Trigger::Account {
-                        address: _,
-                        offset: _,
-                        size: _,
-                    } => TriggerContext::Account { data_hash: 0 },
-                    _ => thread.exec_context.unwrap().trigger_context,
-                },
-                ..thread.exec_context.unwrap()
+                trigger_context: exec_context.trigger_context.reset(&trigger),
+                ..exec_context
             });
         }
     }
@@ -7,7 +7,7 @@ use {
 #[derive(Accounts)]
 #[instruction(amount: u64)]
 pub struct ThreadWithdraw<'info> {
-    /// The authority (owner) of the thread.
+    /// The authority (owner) of the thread, or a delegate with permission to withdraw from it.
     #[account()]
     pub authority: Signer<'info>,
 
@@ -24,16 +24,30 @@ pub struct ThreadWithdraw<'info> {
             thread.id.as_slice(),
         ],
         bump = thread.bump,
-        has_one = authority,
+        constraint = thread.is_authorized(&authority.key(), ThreadPermission::Withdraw) @ ClockworkError::UnauthorizedSigner,
     )]
     pub thread: Account<'info, Thread>,
 }
 
 pub fn handler(ctx: Context<ThreadWithdraw>, amount: u64) -> Result<()> {
     // Get accounts
+    let authority = &ctx.accounts.authority;
     let pay_to = &mut ctx.accounts.pay_to;
     let thread = &mut ctx.accounts.thread;
 
+    // If the signer is a delegate, deduct the amount from its withdraw limit.
+    if authority.key().ne(&thread.current_authority()) {
+        let delegate = thread
+            .delegates
+            .iter_mut()
+            .find(|d| d.address.eq(&authority.key()))
+            .unwrap();
+        delegate.withdraw_limit = delegate
+            .withdraw_limit
+            .checked_sub(amount)
+            .ok_or(ClockworkError::WithdrawLimitExceeded)?;
+    }
+
     // Calculate the minimum rent threshold
     let data_len = 8 + thread.try_to_vec()?.len();
     let minimum_rent = Rent::get().unwrap().minimum_balance(data_len);
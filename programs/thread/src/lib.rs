@@ -29,11 +29,39 @@ pub mod thread_program {
         get_crate_info::handler(ctx)
     }
 
+    /// Creates a fund for topping up the authority's threads.
+    pub fn fund_create(ctx: Context<FundCreate>, amount: u64) -> Result<()> {
+        fund_create::handler(ctx, amount)
+    }
+
+    /// Allows the owner to withdraw SOL from their fund.
+    pub fn fund_withdraw(ctx: Context<FundWithdraw>, amount: u64) -> Result<()> {
+        fund_withdraw::handler(ctx, amount)
+    }
+
     /// Executes the next instruction on thread.
     pub fn thread_exec(ctx: Context<ThreadExec>) -> Result<()> {
         thread_exec::handler(ctx)
     }
 
+    /// Executes an instruction the thread's authority signed off-chain.
+    pub fn thread_exec_intent(ctx: Context<ThreadExecIntent>, intent: ThreadIntent) -> Result<()> {
+        thread_exec_intent::handler(ctx, intent)
+    }
+
+    /// Accepts a pending transfer of the thread to a new authority.
+    pub fn thread_authority_accept(ctx: Context<ThreadAuthorityAccept>) -> Result<()> {
+        thread_authority_accept::handler(ctx)
+    }
+
+    /// Proposes transferring control of the thread to a new authority.
+    pub fn thread_authority_transfer(
+        ctx: Context<ThreadAuthorityTransfer>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        thread_authority_transfer::handler(ctx, new_authority)
+    }
+
     /// Creates a new transaction thread.
     pub fn thread_create(
         ctx: Context<ThreadCreate>,
@@ -41,8 +69,25 @@ pub mod thread_program {
         id: Vec<u8>,
         instructions: Vec<SerializableInstruction>,
         trigger: Trigger,
+        limits: Option<ThreadLimits>,
     ) -> Result<()> {
-        thread_create::handler(ctx, amount, id, instructions, trigger)
+        thread_create::handler(ctx, amount, id, instructions, trigger, limits)
+    }
+
+    /// Grants a delegate scoped permissions to manage the thread on the owner's behalf.
+    pub fn thread_delegate_grant(
+        ctx: Context<ThreadDelegateGrant>,
+        delegate: ThreadDelegate,
+    ) -> Result<()> {
+        thread_delegate_grant::handler(ctx, delegate)
+    }
+
+    /// Revokes all of a delegate's permissions on the thread.
+    pub fn thread_delegate_revoke(
+        ctx: Context<ThreadDelegateRevoke>,
+        address: Pubkey,
+    ) -> Result<()> {
+        thread_delegate_revoke::handler(ctx, address)
     }
 
     /// Closes an existing thread account and returns the lamports to the owner.
@@ -50,6 +95,14 @@ pub mod thread_program {
         thread_delete::handler(ctx)
     }
 
+    /// Sets or clears the configuration for topping up the thread from its authority's fund.
+    pub fn thread_funding_update(
+        ctx: Context<ThreadFundingUpdate>,
+        settings: Option<ThreadFundingSettings>,
+    ) -> Result<()> {
+        thread_funding_update::handler(ctx, settings)
+    }
+
     /// Appends a new instruction to the thread's instruction set.
     pub fn thread_instruction_add(
         ctx: Context<ThreadInstructionAdd>,
@@ -76,6 +129,14 @@ pub mod thread_program {
         thread_pause::handler(ctx)
     }
 
+    /// Records a worker-reported failure of the thread's next instruction and applies its failure policy.
+    pub fn thread_report_failure(
+        ctx: Context<ThreadReportFailure>,
+        error_code: Option<u32>,
+    ) -> Result<()> {
+        thread_report_failure::handler(ctx, error_code)
+    }
+
     /// Resumes a paused thread.
     pub fn thread_resume(ctx: Context<ThreadResume>) -> Result<()> {
         thread_resume::handler(ctx)
@@ -86,6 +147,11 @@ pub mod thread_program {
         thread_reset::handler(ctx)
     }
 
+    /// Arms a thread with a signal trigger, so its next kickoff succeeds.
+    pub fn thread_signal(ctx: Context<ThreadSignal>, payload: Option<Vec<u8>>) -> Result<()> {
+        thread_signal::handler(ctx, payload)
+    }
+
     /// Allows an owner to update the mutable properties of a thread.
     pub fn thread_update(ctx: Context<ThreadUpdate>, settings: ThreadSettings) -> Result<()> {
         thread_update::handler(ctx, settings)
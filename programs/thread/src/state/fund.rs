@@ -0,0 +1,21 @@
+use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
+
+pub const SEED_FUND: &[u8] = b"fund";
+
+/// Holds lamports an authority has set aside to top up its threads.
+/// Anyone may deposit into a fund with a system transfer, but only its authority may withdraw.
+#[account]
+#[derive(Debug)]
+pub struct Fund {
+    /// The owner of this fund.
+    pub authority: Pubkey,
+    /// The bump, used for PDA validation.
+    pub bump: u8,
+}
+
+impl Fund {
+    /// Derive the pubkey of a fund account.
+    pub fn pubkey(authority: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[SEED_FUND, authority.as_ref()], &crate::ID).0
+    }
+}
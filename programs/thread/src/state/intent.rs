@@ -0,0 +1,169 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{ed25519_program, instruction::Instruction},
+    AnchorDeserialize, AnchorSerialize,
+};
+use clockwork_utils::thread::SerializableInstruction;
+
+/// The byte sizes of the parts of an ed25519 program instruction verifying a single signature.
+const ED25519_OFFSETS_START: usize = 2;
+const ED25519_OFFSETS_LEN: usize = 14;
+const ED25519_PUBKEY_LEN: usize = 32;
+const ED25519_SIGNATURE_LEN: usize = 64;
+
+/// The domain tag prefixed to the message an authority signs for an intent,
+/// so the signature cannot be replayed as a signature of any other message.
+pub const INTENT_DOMAIN: &[u8] = b"clockwork-thread-intent";
+
+/// An instruction the thread's authority signed off-chain, for workers to execute through the thread.
+/// The authority signs the intent's `message`: its borsh serialization, prefixed with `INTENT_DOMAIN`.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq)]
+pub struct ThreadIntent {
+    /// The thread to execute the instruction.
+    pub thread: Pubkey,
+    /// The replay nonce. It must be greater than the nonce of the thread's last executed intent.
+    pub nonce: u64,
+    /// The unix timestamp after which the intent can no longer be executed.
+    pub expires_at: i64,
+    /// The instruction to execute.
+    pub instruction: SerializableInstruction,
+}
+
+impl ThreadIntent {
+    /// The message the thread's authority signs.
+    pub fn message(&self) -> Vec<u8> {
+        [INTENT_DOMAIN, self.try_to_vec().unwrap().as_slice()].concat()
+    }
+
+    /// Builds the ed25519 program instruction which verifies the authority's signature of the intent.
+    /// It must directly precede the `thread_exec_intent` instruction in the transaction.
+    pub fn verify_instruction(&self, authority: Pubkey, signature: [u8; 64]) -> Instruction {
+        let message = self.message();
+        let pubkey_offset = ED25519_OFFSETS_START + ED25519_OFFSETS_LEN;
+        let signature_offset = pubkey_offset + ED25519_PUBKEY_LEN;
+        let message_offset = signature_offset + ED25519_SIGNATURE_LEN;
+
+        // Write the number of signatures and a padding byte, then the offsets of the signature, pubkey, and message.
+        // The instruction indices are set to u16::MAX to reference this instruction's own data.
+        let mut data = vec![1, 0];
+        for value in [
+            signature_offset as u16,
+            u16::MAX,
+            pubkey_offset as u16,
+            u16::MAX,
+            message_offset as u16,
+            message.len() as u16,
+            u16::MAX,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(&signature);
+        data.extend_from_slice(&message);
+
+        Instruction {
+            program_id: ed25519_program::ID,
+            accounts: vec![],
+            data,
+        }
+    }
+
+    /// Returns true if the instruction is an ed25519 program instruction verifying
+    /// a single signature of the intent by the authority.
+    pub fn is_verified_by(&self, instruction: &Instruction, authority: &Pubkey) -> bool {
+        if instruction.program_id.ne(&ed25519_program::ID) {
+            return false;
+        }
+        let data = instruction.data.as_slice();
+        if data.first().ne(&Some(&1)) {
+            return false;
+        }
+
+        // Read the offsets, and require they all reference this instruction's own data.
+        let offsets: Vec<usize> =
+            match data.get(ED25519_OFFSETS_START..ED25519_OFFSETS_START + ED25519_OFFSETS_LEN) {
+                None => return false,
+                Some(offsets) => offsets
+                    .chunks(2)
+                    .map(|chunk| u16::from_le_bytes([chunk[0], chunk[1]]) as usize)
+                    .collect(),
+            };
+        let (pubkey_offset, message_offset, message_len) = (offsets[2], offsets[4], offsets[5]);
+        let is_self_referenced = [offsets[1], offsets[3], offsets[6]]
+            .iter()
+            .all(|index| index.eq(&(u16::MAX as usize)));
+
+        is_self_referenced
+            && data
+                .get(pubkey_offset..pubkey_offset + ED25519_PUBKEY_LEN)
+                .eq(&Some(authority.as_ref()))
+            && data
+                .get(message_offset..message_offset + message_len)
+                .eq(&Some(self.message().as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn intent() -> ThreadIntent {
+        ThreadIntent {
+            thread: Pubkey::new_unique(),
+            nonce: 1,
+            expires_at: 100,
+            instruction: Instruction::new_with_bytes(crate::ID, &[1, 2, 3], vec![]).into(),
+        }
+    }
+
+    #[test]
+    fn test_message_domain() {
+        let intent = intent();
+        let message = intent.message();
+        assert!(message.starts_with(INTENT_DOMAIN));
+        assert_eq!(
+            ThreadIntent::try_from_slice(&message[INTENT_DOMAIN.len()..]).unwrap(),
+            intent
+        );
+    }
+
+    #[test]
+    fn test_verify_instruction_round_trip() {
+        let intent = intent();
+        let authority = Pubkey::new_unique();
+        let verify_ix = intent.verify_instruction(authority, [7; 64]);
+        assert!(intent.is_verified_by(&verify_ix, &authority));
+    }
+
+    #[test]
+    fn test_is_verified_by_wrong_pubkey() {
+        let intent = intent();
+        let verify_ix = intent.verify_instruction(Pubkey::new_unique(), [7; 64]);
+        assert!(!intent.is_verified_by(&verify_ix, &Pubkey::new_unique()));
+    }
+
+    #[test]
+    fn test_is_verified_by_wrong_message() {
+        let intent = intent();
+        let authority = Pubkey::new_unique();
+        let verify_ix = intent.verify_instruction(authority, [7; 64]);
+        let other_intent = ThreadIntent {
+            nonce: intent.nonce + 1,
+            ..intent
+        };
+        assert!(!other_intent.is_verified_by(&verify_ix, &authority));
+    }
+
+    #[test]
+    fn test_is_verified_by_wrong_instruction_index() {
+        let intent = intent();
+        let authority = Pubkey::new_unique();
+
+        // Point each of the signature, pubkey, and message at another instruction in the transaction.
+        for index_offset in [4, 8, 14] {
+            let mut verify_ix = intent.verify_instruction(authority, [7; 64]);
+            verify_ix.data[index_offset..index_offset + 2].copy_from_slice(&0u16.to_le_bytes());
+            assert!(!intent.is_verified_by(&verify_ix, &authority));
+        }
+    }
+}
@@ -1,8 +1,12 @@
 //! All objects needed to describe and manage the program's state.
 
+mod fund;
+mod intent;
 mod thread;
 mod versioned_thread;
 
 pub use clockwork_utils::thread::*;
+pub use fund::*;
+pub use intent::*;
 pub use thread::*;
 pub use versioned_thread::*;
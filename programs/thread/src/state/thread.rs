@@ -1,14 +1,35 @@
-use std::mem::size_of;
+use std::{mem::size_of, ops::Deref};
 
 use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
-use clockwork_utils::thread::{ClockData, SerializableInstruction, Trigger};
+use clockwork_utils::thread::{
+    ClockData, DataPatch, DataValue, PatchValue, SerializableInstruction, Trigger, Triggers,
+};
+
+use crate::errors::ClockworkError;
 
 pub use clockwork_utils::thread::Equality;
 
 pub const SEED_THREAD: &[u8] = b"thread";
 
-/// Static space for next_instruction field.
-pub const NEXT_INSTRUCTION_SIZE: usize = 1232;
+pub const SEED_THREAD_SIGNER: &[u8] = b"thread_signer";
+
+/// The maximum number of dynamic instructions a thread may have queued for execution.
+pub const MAX_DYNAMIC_INSTRUCTIONS: usize = 8;
+
+/// The maximum byte size of a thread's memory.
+pub const MAX_MEMORY_SIZE: usize = 1024;
+
+/// The maximum number of derived signers a thread may declare.
+pub const MAX_THREAD_SIGNERS: usize = 4;
+
+/// The maximum byte size of a signal's payload.
+pub const MAX_SIGNAL_PAYLOAD_SIZE: usize = 128;
+
+/// The maximum number of composite triggers which may be nested inside one another.
+pub const MAX_TRIGGER_DEPTH: usize = 2;
+
+/// The maximum number of child triggers a composite trigger may have.
+pub const MAX_TRIGGER_CHILDREN: usize = 8;
 
 /// Tracks the current state of a transaction thread on Solana.
 #[account]
@@ -38,6 +59,39 @@ pub struct Thread {
     pub rate_limit: u64,
     /// The triggering event to kickoff a thread.
     pub trigger: Trigger,
+    /// The limits after which the thread is automatically closed.
+    pub limits: ThreadLimits,
+    /// The address lookup tables workers should use to compress the thread's transactions.
+    pub lookup_tables: Vec<Pubkey>,
+    /// The dynamic instructions queued to be executed after the next instruction.
+    pub dynamic_instructions: Vec<SerializableInstruction>,
+    /// The keys the authority has allowed to manage the thread on its behalf.
+    pub delegates: Vec<ThreadDelegate>,
+    /// The authority controlling the thread, if it has been transferred away from the authority the thread's address is derived from.
+    pub controlling_authority: Option<Pubkey>,
+    /// The authority the thread is being transferred to, pending its acceptance.
+    pub pending_authority: Option<Pubkey>,
+    /// The policy for handling repeated exec failures reported by workers.
+    pub failure_policy: FailurePolicy,
+    /// The maximum priority fee workers may pay, and be reimbursed for, when executing the thread.
+    pub priority_fee: PriorityFee,
+    /// The configuration for topping up the thread from its authority's fund, if enabled.
+    pub funding: Option<ThreadFunding>,
+    /// The caps on the lamports the thread may spend on execs.
+    pub spending_limits: SpendingLimits,
+    /// The thread's lifetime execution statistics.
+    pub stats: ThreadStats,
+    /// The thread's persistent scratch memory, written by its target programs through thread responses.
+    /// Its size is fixed by the authority, and it is empty if disabled.
+    pub memory: Vec<u8>,
+    /// The PDAs derived from the thread which it can also sign for.
+    pub signers: Vec<ThreadSigner>,
+    /// The nonce of the last intent executed by the thread.
+    pub intent_nonce: u64,
+    /// The counters the thread keeps across its runs.
+    pub exec_counters: ExecCounters,
+    /// The data patches applied to the thread's instructions before they are executed.
+    pub instruction_patches: Vec<InstructionPatches>,
 }
 
 impl Thread {
@@ -51,6 +105,60 @@ impl Thread {
     }
 }
 
+impl Thread {
+    /// Returns the authority currently in control of the thread.
+    pub fn current_authority(&self) -> Pubkey {
+        self.controlling_authority.unwrap_or(self.authority)
+    }
+
+    /// Returns the data patches of the instruction at the given index of the instruction set.
+    pub fn patches(&self, index: u64) -> &[DataPatch] {
+        self.instruction_patches
+            .iter()
+            .find(|instruction_patches| instruction_patches.index.eq(&index))
+            .map_or(&[], |instruction_patches| {
+                instruction_patches.patches.as_slice()
+            })
+    }
+
+    /// Verifies the instruction patches each refer to a different instruction in the instruction set.
+    pub fn validate_instruction_patches(&self) -> Result<()> {
+        for (i, instruction_patches) in self.instruction_patches.iter().enumerate() {
+            require!(
+                (instruction_patches.index as usize) < self.instructions.len()
+                    && !self.instruction_patches[..i]
+                        .iter()
+                        .any(|prior| prior.index.eq(&instruction_patches.index)),
+                ClockworkError::InvalidDataPatch
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the delegate with the given address, if one has been granted.
+    pub fn delegate(&self, address: &Pubkey) -> Option<&ThreadDelegate> {
+        self.delegates.iter().find(|d| d.address.eq(address))
+    }
+
+    /// Returns true if the signer is the thread's authority, or a delegate with the given permission.
+    pub fn is_authorized(&self, signer: &Pubkey, permission: ThreadPermission) -> bool {
+        self.current_authority().eq(signer)
+            || self
+                .delegate(signer)
+                .map_or(false, |delegate| delegate.has_permission(permission))
+    }
+
+    /// Returns true if the thread has reached its exec limit or expiry time.
+    pub fn is_expired(&self, clock: &Clock) -> bool {
+        self.limits.max_execs.map_or(false, |max_execs| {
+            self.exec_counters.execs_since_creation.ge(&max_execs)
+        }) || self
+            .limits
+            .expires_at
+            .map_or(false, |expires_at| clock.unix_timestamp.ge(&expires_at))
+    }
+}
+
 impl PartialEq for Thread {
     fn eq(&self, other: &Self) -> bool {
         self.authority.eq(&other.authority) && self.id.eq(&other.id)
@@ -79,9 +187,16 @@ impl ThreadAccount for Account<'_, Thread> {
             8,
             size_of::<Thread>(),
             self.id.len(),
+            self.exec_context.try_to_vec()?.len(),
             self.instructions.try_to_vec()?.len(),
             self.trigger.try_to_vec()?.len(),
-            NEXT_INSTRUCTION_SIZE,
+            self.lookup_tables.try_to_vec()?.len(),
+            self.next_instruction.try_to_vec()?.len(),
+            self.dynamic_instructions.try_to_vec()?.len(),
+            self.delegates.try_to_vec()?.len(),
+            self.memory.len(),
+            self.signers.try_to_vec()?.len(),
+            self.instruction_patches.try_to_vec()?.len(),
         ]
         .iter()
         .sum();
@@ -91,7 +206,7 @@ impl ThreadAccount for Account<'_, Thread> {
 }
 
 /// The execution context of a particular transaction thread.
-#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq, Eq)]
 pub struct ExecContext {
     /// Index of the next instruction to be executed.
     pub exec_index: u64,
@@ -110,10 +225,48 @@ pub struct ExecContext {
     pub trigger_context: TriggerContext,
 }
 
+/// The counters a thread keeps across its runs.
+/// They are stored apart from the exec context, which is reset at each kickoff.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExecCounters {
+    /// Number of exec failures reported by workers since the last successful exec.
+    pub consecutive_failures: u64,
+
+    /// Number of execs since the thread was created.
+    pub execs_since_creation: u64,
+
+    /// Error code of the last exec failure reported by a worker, if it had one.
+    pub last_error_code: Option<u32>,
+
+    /// Slot of the last exec failure reported by a worker.
+    pub last_failure_at: Option<u64>,
+
+    /// Number of runs the thread has completed since it was created.
+    /// A run is complete once the thread has no next instruction to execute.
+    pub runs_completed: u64,
+
+    /// Number of lamports spent on execs in the current spending window.
+    pub window_spent: u64,
+
+    /// Slot the current spending window started at.
+    pub window_started_at: u64,
+}
+
+/// The data patches applied to one of a thread's instructions before it is executed.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq)]
+pub struct InstructionPatches {
+    /// The index of the instruction in the thread's instruction set.
+    pub index: u64,
+
+    /// The patches to write into the instruction's data.
+    pub patches: Vec<DataPatch>,
+}
+
 /// The event which allowed a particular transaction thread to be triggered.
-#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq, Eq)]
 pub enum TriggerContext {
-    /// A running hash of the observed account data.
+    /// A legacy hash of the observed account data, computed with the unspecified std hasher.
+    /// Superseded by `AccountHash`, and treated as unknown by account triggers.
     Account {
         /// The account's data hash.
         data_hash: u64,
@@ -148,14 +301,565 @@ pub enum TriggerContext {
 
     /// The trigger context for threads with a "pyth" trigger.
     Pyth { price: i64 },
+
+    /// The trigger context for threads with an "any" trigger.
+    Any {
+        /// The contexts of the child triggers, in the same order as the trigger's children.
+        contexts: TriggerContexts,
+    },
+
+    /// The trigger context for threads with an "all" trigger.
+    All {
+        /// The contexts of the child triggers, in the same order as the trigger's children.
+        contexts: TriggerContexts,
+    },
+
+    /// The trigger context for threads with an "account value" trigger.
+    AccountValue {
+        /// The last recorded value of the monitored account data.
+        value: DataValue,
+    },
+
+    /// The trigger context for threads with a "slot interval" trigger.
+    SlotInterval {
+        /// The last slot boundary the thread was kicked off at.
+        started_at: u64,
+    },
+
+    /// The trigger context for threads with an "epoch interval" trigger.
+    EpochInterval {
+        /// The last epoch boundary the thread was kicked off at.
+        started_at: u64,
+    },
+
+    /// The trigger context for threads with a "thread completed" trigger.
+    ThreadCompleted {
+        /// The upstream thread's completed run this thread was last kicked off for,
+        /// or the upstream thread's completed runs when the trigger was first observed.
+        run: u64,
+    },
+
+    /// The trigger context for threads with an "oracle" trigger.
+    Oracle {
+        /// The last recorded price, scaled to the trigger's decimals.
+        price: i64,
+    },
+
+    /// The trigger context for threads with a "token balance" trigger.
+    TokenBalance {
+        /// The last recorded balance of the monitored token account.
+        amount: u64,
+    },
+
+    /// A SHA-256 hash of the observed account data, as computed by `clockwork_utils::thread::hash_account_data`.
+    AccountHash {
+        /// The account's data hash.
+        data_hash: [u8; 32],
+    },
+
+    /// The trigger context for threads with a "signal" trigger.
+    Signal {
+        /// True if the thread has been signaled and not yet kicked off.
+        armed: bool,
+        /// The payload of the last signal.
+        payload: Option<Vec<u8>>,
+    },
+}
+
+/// The trigger contexts of a composite trigger's children.
+/// A child which has never been active has no context.
+/// The (de)serializers are written by hand since the derive macros can't handle the recursion through `TriggerContext`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TriggerContexts(pub Vec<Option<TriggerContext>>);
+
+impl AnchorSerialize for TriggerContexts {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+impl AnchorDeserialize for TriggerContexts {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(TriggerContexts(Vec::<Option<TriggerContext>>::deserialize(
+            buf,
+        )?))
+    }
+}
+
+impl Deref for TriggerContexts {
+    type Target = Vec<Option<TriggerContext>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl TriggerContext {
+    /// Returns the value a data patch reads from this trigger context, as little-endian bytes.
+    /// Composite contexts return the value of their first child context which has one.
+    pub fn patch_value(&self, value: &PatchValue) -> Option<[u8; 8]> {
+        match (value, self) {
+            (
+                PatchValue::TriggerStartedAt,
+                TriggerContext::Cron { started_at } | TriggerContext::Timestamp { started_at },
+            ) => Some(started_at.to_le_bytes()),
+            (
+                PatchValue::TriggerStartedAt,
+                TriggerContext::Slot { started_at }
+                | TriggerContext::Epoch { started_at }
+                | TriggerContext::SlotInterval { started_at }
+                | TriggerContext::EpochInterval { started_at },
+            ) => Some(started_at.to_le_bytes()),
+            (
+                PatchValue::TriggerPrice,
+                TriggerContext::Pyth { price } | TriggerContext::Oracle { price },
+            ) => Some(price.to_le_bytes()),
+            (PatchValue::TriggerAmount, TriggerContext::TokenBalance { amount }) => {
+                Some(amount.to_le_bytes())
+            }
+            (_, TriggerContext::Any { contexts } | TriggerContext::All { contexts }) => contexts
+                .iter()
+                .flatten()
+                .find_map(|context| context.patch_value(value)),
+            _ => None,
+        }
+    }
+
+    /// Re-prime the trigger context after the thread's trigger has been updated.
+    /// Account trigger contexts are reset to zero since the prior data hash is no longer valid.
+    pub fn reset(&self, trigger: &Trigger) -> TriggerContext {
+        match (trigger, self) {
+            (Trigger::Account { .. } | Trigger::AccountComponents { .. }, _) => {
+                TriggerContext::AccountHash { data_hash: [0; 32] }
+            }
+            (Trigger::Any { triggers }, TriggerContext::Any { contexts }) => TriggerContext::Any {
+                contexts: reset_child_contexts(triggers, contexts),
+            },
+            (Trigger::All { triggers }, TriggerContext::All { contexts }) => TriggerContext::All {
+                contexts: reset_child_contexts(triggers, contexts),
+            },
+            _ => self.clone(),
+        }
+    }
+}
+
+/// Verifies a trigger's composite triggers are within the maximum depth and number of children,
+/// so kickoffs can always verify the trigger within the stack and compute limits.
+/// Signal triggers can only be armed by `thread_signal` at the top level, so they cannot be nested.
+pub fn validate_trigger(trigger: &Trigger) -> Result<()> {
+    validate_composite_trigger(trigger, 0)
+}
+
+fn validate_composite_trigger(trigger: &Trigger, depth: usize) -> Result<()> {
+    match trigger {
+        Trigger::Any { triggers } | Trigger::All { triggers } => {
+            require!(
+                depth < MAX_TRIGGER_DEPTH && triggers.len() <= MAX_TRIGGER_CHILDREN,
+                ClockworkError::InvalidCompositeTrigger
+            );
+            triggers
+                .iter()
+                .try_for_each(|trigger| validate_composite_trigger(trigger, depth + 1))
+        }
+        Trigger::Signal { .. } if depth > 0 => Err(ClockworkError::InvalidCompositeTrigger.into()),
+        _ => Ok(()),
+    }
+}
+
+fn reset_child_contexts(triggers: &Triggers, contexts: &TriggerContexts) -> TriggerContexts {
+    // If the set of child triggers has changed size, the prior contexts can no longer be matched up.
+    if triggers.len() != contexts.len() {
+        return TriggerContexts(vec![None; triggers.len()]);
+    }
+    TriggerContexts(
+        triggers
+            .iter()
+            .zip(contexts.iter())
+            .map(|(trigger, context)| context.as_ref().map(|c| c.reset(trigger)))
+            .collect(),
+    )
 }
 
 /// The properties of threads which are updatable.
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct ThreadSettings {
+    pub failure_policy: Option<FailurePolicy>,
     pub fee: Option<u64>,
     pub instructions: Option<Vec<SerializableInstruction>>,
+    pub instruction_patches: Option<Vec<InstructionPatches>>,
+    pub limits: Option<ThreadLimits>,
+    pub lookup_tables: Option<Vec<Pubkey>>,
+    pub memory_size: Option<u32>,
     pub name: Option<String>,
+    pub priority_fee: Option<PriorityFee>,
     pub rate_limit: Option<u64>,
+    pub signers: Option<Vec<Vec<u8>>>,
+    pub spending_limits: Option<SpendingLimits>,
     pub trigger: Option<Trigger>,
 }
+
+/// The limits after which a thread schedules its own deletion.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ThreadLimits {
+    /// The address to return the thread's lamports to when it is closed. Defaults to the thread's current authority.
+    pub close_to: Option<Pubkey>,
+    /// The unix timestamp at or after which the thread is closed.
+    pub expires_at: Option<i64>,
+    /// The total number of execs after which the thread is closed.
+    pub max_execs: Option<u64>,
+}
+
+/// The maximum priority fee workers may pay to land a thread's transactions.
+/// Workers are reimbursed for the priority fee they pay, up to these caps.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PriorityFee {
+    /// The maximum compute unit price, in micro-lamports per compute unit.
+    pub max_compute_unit_price: u64,
+    /// The maximum priority fee per transaction, in lamports.
+    pub max_fee_per_tx: u64,
+}
+
+/// The configuration for topping up a thread's balance from its authority's fund.
+/// Top-ups are bounded by an allowance which renews every period.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ThreadFunding {
+    /// The maximum number of lamports drawn from the fund per period.
+    pub allowance: u64,
+    /// The balance below which the thread is topped up, in lamports.
+    pub floor: u64,
+    /// The length of an allowance period, in seconds.
+    pub period: u64,
+    /// The number of lamports drawn from the fund in the current period.
+    pub period_spent: u64,
+    /// The unix timestamp the current period started at.
+    pub period_started_at: i64,
+    /// The balance the thread is topped up to, in lamports.
+    pub target: u64,
+}
+
+impl ThreadFunding {
+    /// Returns the number of lamports to top up a thread with the given balance, and records them against the allowance.
+    /// The top-up is limited by the period's remaining allowance, and by the lamports available in the fund.
+    pub fn top_up(&mut self, balance: u64, available: u64, now: i64) -> u64 {
+        if balance.ge(&self.floor) {
+            return 0;
+        }
+
+        // If the current period has elapsed, start a new one.
+        if now.saturating_sub(self.period_started_at) as i128 >= self.period as i128 {
+            self.period_spent = 0;
+            self.period_started_at = now;
+        }
+
+        let amount = self
+            .target
+            .saturating_sub(balance)
+            .min(self.allowance.saturating_sub(self.period_spent))
+            .min(available);
+        self.period_spent = self.period_spent.saturating_add(amount);
+        amount
+    }
+}
+
+/// The funding properties of threads which are updatable.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct ThreadFundingSettings {
+    pub allowance: u64,
+    pub floor: u64,
+    pub period: u64,
+    pub target: u64,
+}
+
+impl From<ThreadFundingSettings> for ThreadFunding {
+    fn from(settings: ThreadFundingSettings) -> Self {
+        ThreadFunding {
+            allowance: settings.allowance,
+            floor: settings.floor,
+            period: settings.period,
+            period_spent: 0,
+            period_started_at: 0,
+            target: settings.target,
+        }
+    }
+}
+
+/// The caps on the lamports a thread may spend on execs, including signatory reimbursements and worker fees.
+/// Execs which would exceed a cap fail.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpendingLimits {
+    /// The maximum number of lamports the signatory may be reimbursed per exec. If none, reimbursements are not capped.
+    pub max_reimbursement_per_exec: Option<u64>,
+    /// The maximum number of lamports spent per window. If none, spending is not capped.
+    pub max_spent_per_window: Option<u64>,
+    /// The length of a spending window, in slots.
+    pub window_slots: u64,
+}
+
+/// A PDA derived from a thread and a seed, which the thread can sign for.
+/// It lets a thread own token vaults and other accounts apart from the thread account itself.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct ThreadSigner {
+    /// The bump, used for PDA derivation.
+    pub bump: u8,
+    /// The seed appended to the thread's pubkey.
+    pub seed: Vec<u8>,
+}
+
+impl ThreadSigner {
+    /// Derive a signer of the thread from the seed.
+    pub fn new(thread: Pubkey, seed: Vec<u8>) -> Self {
+        let (_, bump) = Pubkey::find_program_address(
+            &[SEED_THREAD_SIGNER, thread.as_ref(), seed.as_slice()],
+            &crate::ID,
+        );
+        Self { bump, seed }
+    }
+
+    /// Get the pubkey of the signer.
+    pub fn pubkey(&self, thread: Pubkey) -> Pubkey {
+        Pubkey::create_program_address(
+            &[
+                SEED_THREAD_SIGNER,
+                thread.as_ref(),
+                self.seed.as_slice(),
+                &[self.bump],
+            ],
+            &crate::ID,
+        )
+        .unwrap()
+    }
+}
+
+/// The lifetime execution statistics of a thread.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ThreadStats {
+    /// The total number of execs.
+    pub execs: u64,
+    /// The total number of lamports paid to workers as exec fees.
+    pub fees_paid: u64,
+    /// The slot of the last successful exec.
+    pub last_success_at: Option<u64>,
+    /// The last worker to execute the thread.
+    pub last_worker: Option<Pubkey>,
+    /// The total number of lamports reimbursed to workers' signatories for transaction fees and payer spending.
+    pub reimbursements: u64,
+    /// The total number of runs kicked off.
+    pub runs: u64,
+}
+
+/// The policy for handling a thread's repeated exec failures, as reported by workers.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FailurePolicy {
+    /// The action to take once the thread reaches its failure limit.
+    pub action: FailureAction,
+    /// The number of consecutive failures at which the action is taken. If none, failures are only recorded.
+    pub max_consecutive_failures: Option<u64>,
+}
+
+/// The actions a thread can take when its next instruction keeps failing.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FailureAction {
+    /// Pause the thread until its authority resumes it.
+    #[default]
+    Pause,
+    /// Skip the failing instruction and move on to the next one.
+    SkipInstruction,
+    /// End the current run without counting it as completed.
+    EndRun,
+}
+
+/// A key which the authority has allowed to manage a thread on its behalf, with scoped permissions.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct ThreadDelegate {
+    /// The delegate's address.
+    pub address: Pubkey,
+    /// Whether the delegate may add and remove instructions from the thread's instruction set.
+    pub can_edit_instructions: bool,
+    /// Whether the delegate may pause and resume the thread.
+    pub can_pause: bool,
+    /// Whether the delegate may update the thread's trigger.
+    pub can_update_trigger: bool,
+    /// The number of lamports the delegate may still withdraw from the thread.
+    pub withdraw_limit: u64,
+}
+
+impl ThreadDelegate {
+    /// Returns true if the delegate has been granted the permission.
+    pub fn has_permission(&self, permission: ThreadPermission) -> bool {
+        match permission {
+            ThreadPermission::EditInstructions => self.can_edit_instructions,
+            ThreadPermission::Pause => self.can_pause,
+            ThreadPermission::UpdateTrigger => self.can_update_trigger,
+            ThreadPermission::Withdraw => self.withdraw_limit.gt(&0),
+        }
+    }
+}
+
+/// The operations a thread delegate can be permitted to perform.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThreadPermission {
+    /// Add and remove instructions from the thread's instruction set.
+    EditInstructions,
+    /// Pause and resume the thread.
+    Pause,
+    /// Update the thread's trigger.
+    UpdateTrigger,
+    /// Withdraw lamports from the thread, up to the delegate's withdraw limit.
+    Withdraw,
+}
+
+#[cfg(test)]
+mod test {
+    use anchor_lang::{solana_program::instruction::Instruction, Discriminator};
+
+    use super::*;
+
+    /// The layout of thread accounts before the appended fields were added.
+    #[derive(AnchorSerialize)]
+    struct LegacyThread {
+        authority: Pubkey,
+        bump: u8,
+        created_at: ClockData,
+        exec_context: Option<LegacyExecContext>,
+        fee: u64,
+        id: Vec<u8>,
+        instructions: Vec<SerializableInstruction>,
+        name: String,
+        next_instruction: Option<SerializableInstruction>,
+        paused: bool,
+        rate_limit: u64,
+        trigger: Trigger,
+    }
+
+    #[derive(AnchorSerialize)]
+    struct LegacyExecContext {
+        exec_index: u64,
+        execs_since_reimbursement: u64,
+        execs_since_slot: u64,
+        last_exec_at: u64,
+        trigger_context: TriggerContext,
+    }
+
+    fn legacy_instruction(data: u8) -> SerializableInstruction {
+        Instruction::new_with_bytes(crate::ID, &[data], vec![]).into()
+    }
+
+    fn legacy_thread() -> LegacyThread {
+        LegacyThread {
+            authority: Pubkey::new_unique(),
+            bump: 254,
+            created_at: ClockData {
+                slot: 1,
+                epoch: 2,
+                unix_timestamp: 3,
+            },
+            exec_context: Some(LegacyExecContext {
+                exec_index: 4,
+                execs_since_reimbursement: 5,
+                execs_since_slot: 6,
+                last_exec_at: 7,
+                trigger_context: TriggerContext::Cron { started_at: 8 },
+            }),
+            fee: 1000,
+            id: b"legacy".to_vec(),
+            instructions: vec![legacy_instruction(0), legacy_instruction(1)],
+            name: "legacy".into(),
+            next_instruction: Some(legacy_instruction(1)),
+            paused: false,
+            rate_limit: 10,
+            trigger: Trigger::Cron {
+                schedule: "0 * * * * * *".into(),
+                skippable: true,
+            },
+        }
+    }
+
+    fn deserialize_legacy_thread(legacy_thread: &LegacyThread) -> Thread {
+        // Legacy threads were allocated extra space for their next instruction, which is zeroed.
+        let mut data = Thread::DISCRIMINATOR.to_vec();
+        data.extend(legacy_thread.try_to_vec().unwrap());
+        data.extend(vec![0; 1232]);
+        Thread::try_deserialize(&mut data.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn test_legacy_thread_deserializes() {
+        let legacy_thread = legacy_thread();
+        let thread = deserialize_legacy_thread(&legacy_thread);
+        assert_eq!(legacy_thread.authority, thread.authority);
+        assert_eq!(legacy_thread.id, thread.id);
+        assert_eq!(legacy_thread.instructions, thread.instructions);
+        assert_eq!(legacy_thread.next_instruction, thread.next_instruction);
+        assert_eq!(legacy_thread.trigger, thread.trigger);
+        assert_eq!(
+            Some(ExecContext {
+                exec_index: 4,
+                execs_since_reimbursement: 5,
+                execs_since_slot: 6,
+                last_exec_at: 7,
+                trigger_context: TriggerContext::Cron { started_at: 8 },
+            }),
+            thread.exec_context
+        );
+        assert_eq!(10, thread.rate_limit);
+        assert_eq!(ExecCounters::default(), thread.exec_counters);
+        assert_eq!(ThreadStats::default(), thread.stats);
+        assert!(thread.instruction_patches.is_empty());
+    }
+
+    #[test]
+    fn test_validate_instruction_patches() {
+        let mut thread = deserialize_legacy_thread(&legacy_thread());
+        let instruction_patches = |index| InstructionPatches {
+            index,
+            patches: vec![DataPatch {
+                offset: 0,
+                value: PatchValue::Slot,
+            }],
+        };
+
+        thread.instruction_patches = vec![instruction_patches(0), instruction_patches(1)];
+        assert!(thread.validate_instruction_patches().is_ok());
+        assert_eq!(1, thread.patches(1).len());
+
+        // Patches must refer to an instruction in the instruction set.
+        thread.instruction_patches = vec![instruction_patches(2)];
+        assert!(thread.validate_instruction_patches().is_err());
+
+        // Each instruction's patches can only be listed once.
+        thread.instruction_patches = vec![instruction_patches(1), instruction_patches(1)];
+        assert!(thread.validate_instruction_patches().is_err());
+    }
+
+    fn nested_trigger(depth: usize) -> Trigger {
+        (0..depth).fold(Trigger::Now, |trigger, _| Trigger::Any {
+            triggers: Triggers(vec![trigger]),
+        })
+    }
+
+    #[test]
+    fn test_validate_trigger_depth() {
+        assert!(validate_trigger(&Trigger::Now).is_ok());
+        assert!(validate_trigger(&nested_trigger(MAX_TRIGGER_DEPTH)).is_ok());
+        assert!(validate_trigger(&nested_trigger(MAX_TRIGGER_DEPTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_validate_trigger_children() {
+        let trigger = |children: usize| Trigger::All {
+            triggers: Triggers(vec![Trigger::Now; children]),
+        };
+        assert!(validate_trigger(&trigger(MAX_TRIGGER_CHILDREN)).is_ok());
+        assert!(validate_trigger(&trigger(MAX_TRIGGER_CHILDREN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_validate_trigger_signal() {
+        let signal = Trigger::Signal { signers: vec![] };
+        assert!(validate_trigger(&signal).is_ok());
+        assert!(validate_trigger(&Trigger::Any {
+            triggers: Triggers(vec![Trigger::Now, signal]),
+        })
+        .is_err());
+    }
+}
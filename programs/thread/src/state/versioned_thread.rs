@@ -6,9 +6,11 @@ use clockwork_thread_program_v1::{
 use clockwork_utils::thread::SerializableAccount;
 
 use crate::{
-    ClockData, ExecContext, SerializableInstruction, Thread as ThreadV2, Trigger, TriggerContext,
+    ClockData, ExecContext, ExecCounters, PriorityFee, SerializableInstruction, Thread as ThreadV2,
+    ThreadSigner, ThreadStats, Trigger, TriggerContext,
 };
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug, PartialEq)]
 pub enum VersionedThread {
     V1(ThreadV1),
@@ -34,6 +36,13 @@ impl VersionedThread {
         }
     }
 
+    pub fn dynamic_instructions(&self) -> Vec<SerializableInstruction> {
+        match self {
+            Self::V1(_) => vec![],
+            Self::V2(t) => t.dynamic_instructions.clone(),
+        }
+    }
+
     pub fn exec_context(&self) -> Option<ExecContext> {
         match self {
             Self::V1(t) => t.exec_context.map(|e| ExecContext {
@@ -41,11 +50,22 @@ impl VersionedThread {
                 execs_since_reimbursement: e.execs_since_reimbursement,
                 execs_since_slot: e.execs_since_slot,
                 last_exec_at: e.last_exec_at,
-                trigger_context: unsafe {
-                    std::mem::transmute::<TriggerContextV1, TriggerContext>(e.trigger_context)
+                trigger_context: match e.trigger_context {
+                    TriggerContextV1::Account { data_hash } => {
+                        TriggerContext::Account { data_hash }
+                    }
+                    TriggerContextV1::Cron { started_at } => TriggerContext::Cron { started_at },
+                    TriggerContextV1::Immediate => TriggerContext::Now,
                 },
             }),
-            Self::V2(t) => t.exec_context,
+            Self::V2(t) => t.exec_context.clone(),
+        }
+    }
+
+    pub fn exec_counters(&self) -> ExecCounters {
+        match self {
+            Self::V1(_) => ExecCounters::default(),
+            Self::V2(t) => t.exec_counters.clone(),
         }
     }
 
@@ -56,6 +76,20 @@ impl VersionedThread {
         }
     }
 
+    pub fn lookup_tables(&self) -> Vec<Pubkey> {
+        match self {
+            Self::V1(_) => vec![],
+            Self::V2(t) => t.lookup_tables.clone(),
+        }
+    }
+
+    pub fn memory(&self) -> Vec<u8> {
+        match self {
+            Self::V1(_) => vec![],
+            Self::V2(t) => t.memory.clone(),
+        }
+    }
+
     pub fn next_instruction(&self) -> Option<SerializableInstruction> {
         match self {
             Self::V1(t) => match &t.next_instruction {
@@ -86,6 +120,13 @@ impl VersionedThread {
         }
     }
 
+    pub fn priority_fee(&self) -> PriorityFee {
+        match self {
+            Self::V1(_) => PriorityFee::default(),
+            Self::V2(t) => t.priority_fee.clone(),
+        }
+    }
+
     pub fn program_id(&self) -> Pubkey {
         match self {
             Self::V1(_) => clockwork_thread_program_v1::ID,
@@ -109,6 +150,20 @@ impl VersionedThread {
         }
     }
 
+    pub fn signers(&self) -> Vec<ThreadSigner> {
+        match self {
+            Self::V1(_) => vec![],
+            Self::V2(t) => t.signers.clone(),
+        }
+    }
+
+    pub fn stats(&self) -> ThreadStats {
+        match self {
+            Self::V1(_) => ThreadStats::default(),
+            Self::V2(t) => t.stats.clone(),
+        }
+    }
+
     pub fn trigger(&self) -> Trigger {
         match self {
             Self::V1(t) => match &t.trigger {
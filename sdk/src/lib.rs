@@ -4,32 +4,85 @@ pub use clockwork_thread_program::ID;
 
 pub mod state {
     pub use clockwork_thread_program::state::{
-        ClockData, ExecContext, SerializableAccount, SerializableInstruction, Thread,
-        ThreadAccount, ThreadResponse, ThreadSettings, Trigger, TriggerContext,
+        ClockData, ExecContext, ExecCounters, FailureAction, FailurePolicy, Fund, OraclePrice,
+        OracleSource, PriorityFee, SerializableAccount, SerializableInstruction, SpendingLimits,
+        Thread, ThreadAccount, ThreadDelegate, ThreadFunding, ThreadFundingSettings, ThreadIntent,
+        ThreadLimits, ThreadPermission, ThreadResponse, ThreadSettings, ThreadSigner, ThreadStats,
+        Trigger, TriggerContext, TriggerContexts, Triggers,
     };
 }
 
 pub mod utils {
     pub use clockwork_thread_program::state::PAYER_PUBKEY;
     pub use clockwork_thread_program::state::Equality;
+    pub use clockwork_thread_program::state::DataValue;
+    pub use clockwork_thread_program::state::PythMode;
+    pub use clockwork_thread_program::state::INTENT_DOMAIN;
+    pub use clockwork_thread_program::state::AccountComponent;
+    pub use clockwork_thread_program::state::{DataPatch, InstructionPatches, PatchValue};
+    pub use clockwork_thread_program::state::{FEE_PUBKEY, THREAD_PUBKEY, WORKER_PUBKEY};
+    pub use clockwork_thread_program::state::{thread_signer_placeholder, THREAD_SIGNER_PUBKEY};
 }
 
 pub mod cpi {
     use anchor_lang::prelude::{CpiContext, Result};
 
     pub use clockwork_thread_program::cpi::accounts::{
-        ThreadCreate, ThreadDelete, ThreadPause, ThreadReset, ThreadResume, ThreadUpdate,
-        ThreadWithdraw,
+        FundCreate, FundWithdraw, ThreadAuthorityAccept, ThreadAuthorityTransfer, ThreadCreate,
+        ThreadDelegateGrant, ThreadDelegateRevoke, ThreadDelete, ThreadFundingUpdate, ThreadPause,
+        ThreadReset, ThreadResume, ThreadSignal, ThreadUpdate, ThreadWithdraw,
     };
 
+    pub fn fund_create<'info>(
+        ctx: CpiContext<'_, '_, '_, 'info, FundCreate<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        clockwork_thread_program::cpi::fund_create(ctx, amount)
+    }
+
+    pub fn fund_withdraw<'info>(
+        ctx: CpiContext<'_, '_, '_, 'info, FundWithdraw<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        clockwork_thread_program::cpi::fund_withdraw(ctx, amount)
+    }
+
+    pub fn thread_authority_accept<'info>(
+        ctx: CpiContext<'_, '_, '_, 'info, ThreadAuthorityAccept<'info>>,
+    ) -> Result<()> {
+        clockwork_thread_program::cpi::thread_authority_accept(ctx)
+    }
+
+    pub fn thread_authority_transfer<'info>(
+        ctx: CpiContext<'_, '_, '_, 'info, ThreadAuthorityTransfer<'info>>,
+        new_authority: anchor_lang::prelude::Pubkey,
+    ) -> Result<()> {
+        clockwork_thread_program::cpi::thread_authority_transfer(ctx, new_authority)
+    }
+
     pub fn thread_create<'info>(
         ctx: CpiContext<'_, '_, '_, 'info, ThreadCreate<'info>>,
         amount: u64,
         id: Vec<u8>,
         instructions: Vec<crate::state::SerializableInstruction>,
         trigger: crate::state::Trigger,
+        limits: Option<crate::state::ThreadLimits>,
+    ) -> Result<()> {
+        clockwork_thread_program::cpi::thread_create(ctx, amount, id, instructions, trigger, limits)
+    }
+
+    pub fn thread_delegate_grant<'info>(
+        ctx: CpiContext<'_, '_, '_, 'info, ThreadDelegateGrant<'info>>,
+        delegate: crate::state::ThreadDelegate,
+    ) -> Result<()> {
+        clockwork_thread_program::cpi::thread_delegate_grant(ctx, delegate)
+    }
+
+    pub fn thread_delegate_revoke<'info>(
+        ctx: CpiContext<'_, '_, '_, 'info, ThreadDelegateRevoke<'info>>,
+        address: anchor_lang::prelude::Pubkey,
     ) -> Result<()> {
-        clockwork_thread_program::cpi::thread_create(ctx, amount, id, instructions, trigger)
+        clockwork_thread_program::cpi::thread_delegate_revoke(ctx, address)
     }
 
     pub fn thread_delete<'info>(
@@ -38,6 +91,13 @@ pub mod cpi {
         clockwork_thread_program::cpi::thread_delete(ctx)
     }
 
+    pub fn thread_funding_update<'info>(
+        ctx: CpiContext<'_, '_, '_, 'info, ThreadFundingUpdate<'info>>,
+        settings: Option<crate::state::ThreadFundingSettings>,
+    ) -> Result<()> {
+        clockwork_thread_program::cpi::thread_funding_update(ctx, settings)
+    }
+
     pub fn thread_pause<'info>(
         ctx: CpiContext<'_, '_, '_, 'info, ThreadPause<'info>>,
     ) -> Result<()> {
@@ -56,6 +116,13 @@ pub mod cpi {
         clockwork_thread_program::cpi::thread_reset(ctx)
     }
 
+    pub fn thread_signal<'info>(
+        ctx: CpiContext<'_, '_, '_, 'info, ThreadSignal<'info>>,
+        payload: Option<Vec<u8>>,
+    ) -> Result<()> {
+        clockwork_thread_program::cpi::thread_signal(ctx, payload)
+    }
+
     pub fn thread_update<'info>(
         ctx: CpiContext<'_, '_, '_, 'info, ThreadUpdate<'info>>,
         settings: crate::state::ThreadSettings,
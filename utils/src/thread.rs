@@ -1,4 +1,4 @@
-use std::{convert::TryFrom, fmt::Debug, hash::Hash};
+use std::{convert::TryFrom, fmt::Debug, hash::Hash, ops::Deref};
 
 use anchor_lang::{
     prelude::borsh::BorshSchema,
@@ -13,6 +13,35 @@ use static_pubkey::static_pubkey;
 /// The stand-in pubkey for delegating a payer address to a worker. All workers are re-imbursed by the user for lamports spent during this delegation.
 pub static PAYER_PUBKEY: Pubkey = static_pubkey!("C1ockworkPayer11111111111111111111111111111");
 
+/// The stand-in pubkey for the executing thread's own address.
+pub static THREAD_PUBKEY: Pubkey = static_pubkey!("C1ockworkThread1111111111111111111111111111");
+
+/// The stand-in pubkey for the executing worker's address.
+pub static WORKER_PUBKEY: Pubkey = static_pubkey!("C1ockworkWorker1111111111111111111111111111");
+
+/// The stand-in pubkey for the executing worker's fee account address.
+pub static FEE_PUBKEY: Pubkey = static_pubkey!("C1ockworkFee1111111111111111111111111111111");
+
+/// The base of the stand-in pubkeys for a thread's derived signers.
+/// Its last byte is replaced with the index of the signer, see `thread_signer_placeholder`.
+pub static THREAD_SIGNER_PUBKEY: Pubkey =
+    static_pubkey!("C1ockworkThreadSigner1111111111111111111111");
+
+/// Returns the stand-in pubkey for the thread's derived signer at the given index.
+pub fn thread_signer_placeholder(index: u8) -> Pubkey {
+    let mut bytes = THREAD_SIGNER_PUBKEY.to_bytes();
+    bytes[31] = index;
+    Pubkey::new_from_array(bytes)
+}
+
+/// Returns the index of the thread's derived signer a stand-in pubkey refers to, if it is one.
+pub fn thread_signer_index(pubkey: &Pubkey) -> Option<u8> {
+    let bytes = pubkey.to_bytes();
+    bytes[..31]
+        .eq(&THREAD_SIGNER_PUBKEY.to_bytes()[..31])
+        .then(|| bytes[31])
+}
+
 /// The clock object, representing a specific moment in time recorded by a Solana cluster.
 #[derive(AnchorDeserialize, AnchorSerialize, BorshSchema, Clone, Debug, PartialEq)]
 pub struct ClockData {
@@ -80,14 +109,196 @@ pub enum Trigger {
     Timestamp { unix_ts: i64 },
 
     /// Allows a thread to be kicked off according to a Pyth price feed movement.
+    /// Prices older than `DEFAULT_PYTH_STALENESS` seconds are ignored.
     Pyth {
         /// The address of the price feed to monitor.
         price_feed: Pubkey,
-        /// The equality operator (gte or lte) used to compare prices. 
+        /// The equality operator used to compare prices. 
         equality: Equality,
         /// The limit price to compare the Pyth feed to. 
         limit: i64,
     },
+
+    /// Allows a thread to be kicked off when any one of the provided triggers is active.
+    Any {
+        /// The child triggers to monitor.
+        triggers: Triggers,
+    },
+
+    /// Allows a thread to be kicked off only when all of the provided triggers are active.
+    All {
+        /// The child triggers to monitor.
+        triggers: Triggers,
+    },
+
+    /// Allows a thread to be kicked off when a numeric value in an account's data satisfies a condition.
+    AccountValue {
+        /// The address of the account to monitor.
+        address: Pubkey,
+        /// The byte offset of the value in the account data.
+        offset: u64,
+        /// The equality operator used to compare the value to the threshold.
+        equality: Equality,
+        /// The threshold to compare the value to. Its type determines how the account data is read.
+        threshold: DataValue,
+    },
+
+    /// Allows a thread to be kicked off every N slots, starting at a given slot.
+    /// If the thread falls behind, missed boundaries are skipped.
+    SlotInterval {
+        /// The first slot to kick off the thread at.
+        start: u64,
+        /// The number of slots between kickoffs.
+        interval: u64,
+    },
+
+    /// Allows a thread to be kicked off every N epochs, at epoch numbers offset from zero by a fixed amount.
+    /// If the thread falls behind, missed boundaries are skipped.
+    EpochInterval {
+        /// The number of epochs between kickoffs.
+        interval: u64,
+        /// The first epoch to kick off the thread at.
+        offset: u64,
+    },
+
+    /// Allows a thread to be kicked off each time another thread completes a run.
+    ThreadCompleted {
+        /// The address of the upstream thread to monitor.
+        thread: Pubkey,
+    },
+
+    /// Allows a thread to be kicked off according to a price feed movement on any supported oracle.
+    Oracle {
+        /// The oracle program the price feed belongs to.
+        source: OracleSource,
+        /// The address of the price feed to monitor.
+        price_feed: Pubkey,
+        /// The number of decimals the price is scaled to before it is compared to the limit.
+        decimals: u32,
+        /// The equality operator used to compare prices.
+        equality: Equality,
+        /// The limit price to compare the feed to.
+        /// In relative mode, the limit is a price move in basis points instead.
+        limit: i64,
+        /// Whether the limit is compared to the price itself, or to its move since the thread's last run.
+        mode: PythMode,
+        /// The maximum age of the price, in seconds.
+        max_staleness: u64,
+        /// The maximum width of the price's confidence interval, in basis points of the price.
+        /// If none, the confidence interval is not checked.
+        max_confidence_bps: Option<u64>,
+    },
+
+    /// Allows a thread to be kicked off when the balance of an SPL Token or Token-2022 account satisfies a condition.
+    TokenBalance {
+        /// The address of the token account to monitor.
+        token_account: Pubkey,
+        /// The equality operator used to compare the balance to the amount.
+        equality: Equality,
+        /// The amount to compare the balance to, in the mint's base units.
+        amount: u64,
+    },
+
+    /// Allows a thread to be kicked off whenever any of the watched components of an account changes.
+    AccountComponents {
+        /// The address of the account to monitor.
+        address: Pubkey,
+        /// The components of the account to monitor.
+        components: Vec<AccountComponent>,
+    },
+
+    /// Allows a thread to be kicked off once each time it is signaled by one of the allowed signers.
+    Signal {
+        /// The signers allowed to signal the thread.
+        signers: Vec<Pubkey>,
+    },
+
+    /// Allows a thread to be kicked off according to a one-time or recurring schedule in a given timezone.
+    CronTz {
+        /// The schedule in cron syntax. Value must be parsable by the `clockwork_cron` package.
+        schedule: String,
+
+        /// Boolean value indicating whether triggering moments may be skipped if they are missed (e.g. due to network downtime).
+        /// If false, any "missed" triggering moments will simply be executed as soon as the network comes back online.
+        skippable: bool,
+
+        /// The IANA name of the timezone to evaluate the schedule in (e.g. "America/New_York").
+        /// Local times skipped by a daylight saving gap are shifted forward by the length of the gap,
+        /// and local times repeated by a daylight saving overlap only trigger on their first occurrence.
+        timezone: String,
+    },
+
+    /// Allows a thread to be kicked off according to a Pyth price feed movement,
+    /// with configurable staleness and confidence checks.
+    PythPrice {
+        /// The address of the price feed to monitor.
+        price_feed: Pubkey,
+        /// The equality operator used to compare prices.
+        equality: Equality,
+        /// The limit price to compare the Pyth feed to.
+        /// In relative mode, the limit is a price move in basis points instead.
+        limit: i64,
+        /// Whether the limit is compared to the price itself, or to its move since the thread's last run.
+        mode: PythMode,
+        /// The maximum age of the price, in seconds.
+        max_staleness: u64,
+        /// The maximum width of the price's confidence interval, in basis points of the price.
+        /// If none, the confidence interval is not checked.
+        max_confidence_bps: Option<u64>,
+    },
+}
+
+/// The maximum age, in seconds, of the prices read by `Pyth` triggers.
+/// `PythPrice` triggers set their own maximum age.
+pub const DEFAULT_PYTH_STALENESS: u64 = 60;
+
+/// A watched component of an account.
+#[derive(AnchorDeserialize, AnchorSerialize, Debug, Clone, PartialEq)]
+pub enum AccountComponent {
+    /// A byte slice of the account data.
+    Data {
+        /// The byte offset of the account data to monitor.
+        offset: u64,
+        /// The size of the byte slice to monitor.
+        size: u64,
+    },
+
+    /// The account's lamport balance.
+    Lamports,
+
+    /// The account's owner program.
+    Owner,
+}
+
+/// The list of child triggers of a composite trigger.
+/// Borsh cannot derive (de)serializers for recursive types, so they are implemented by hand here.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Triggers(pub Vec<Trigger>);
+
+impl AnchorSerialize for Triggers {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+impl AnchorDeserialize for Triggers {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        Ok(Triggers(Vec::<Trigger>::deserialize(buf)?))
+    }
+}
+
+impl Deref for Triggers {
+    type Target = Vec<Trigger>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Vec<Trigger>> for Triggers {
+    fn from(triggers: Vec<Trigger>) -> Self {
+        Triggers(triggers)
+    }
 }
 
 /// Operators for describing how to compare two values to one another.  
@@ -96,19 +307,360 @@ pub enum Trigger {
 pub enum Equality {
     GreaterThanOrEqual,
     LessThanOrEqual,
+    /// The value is equal to the threshold.
+    Equal,
+    /// The value is not equal to the threshold.
+    NotEqual,
+    /// The value has risen from below the threshold to at or above it.
+    CrossesUp,
+    /// The value has fallen from at or above the threshold to below it.
+    CrossesDown,
+}
+
+impl Equality {
+    /// Returns true if the observed value satisfies the operator.
+    /// The crossing operators also require the prior recorded value (if any) to be on the other side of the threshold.
+    pub fn is_satisfied<T: PartialOrd>(
+        &self,
+        value: &T,
+        threshold: &T,
+        prior: Option<&T>,
+    ) -> bool {
+        match self {
+            Equality::GreaterThanOrEqual => value.ge(threshold),
+            Equality::LessThanOrEqual => value.le(threshold),
+            Equality::Equal => value.eq(threshold),
+            Equality::NotEqual => value.ne(threshold),
+            Equality::CrossesUp => {
+                value.ge(threshold) && prior.map_or(true, |p| p.lt(threshold))
+            }
+            Equality::CrossesDown => {
+                value.lt(threshold) && prior.map_or(true, |p| p.ge(threshold))
+            }
+        }
+    }
+
+    /// Returns true if a crossing operator has been re-armed, i.e. the value has moved back across
+    /// the threshold since it was last recorded. Re-arming records the value without firing the thread.
+    pub fn is_rearmed<T: PartialOrd>(&self, value: &T, threshold: &T, prior: Option<&T>) -> bool {
+        match self {
+            Equality::CrossesUp => {
+                value.lt(threshold) && prior.map_or(false, |p| p.ge(threshold))
+            }
+            Equality::CrossesDown => {
+                value.ge(threshold) && prior.map_or(false, |p| p.lt(threshold))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// How a Pyth trigger's limit is compared to a price feed.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum PythMode {
+    /// Compare the price to the limit.
+    Absolute,
+    /// Compare the price's move since the thread's last run, in basis points, to the limit.
+    /// For example, `GreaterThanOrEqual` with a limit of 100 is satisfied once the price has risen 1%,
+    /// and `LessThanOrEqual` with a limit of -100 once it has fallen 1%.
+    Relative,
+}
+
+impl PythMode {
+    /// Returns the value to compare to the trigger's limit.
+    /// Returns None in relative mode if there is no prior price to measure the move from.
+    pub fn value(&self, price: i64, prior_price: Option<i64>) -> Option<i64> {
+        match self {
+            PythMode::Absolute => Some(price),
+            PythMode::Relative => {
+                let prior_price = prior_price.filter(|p| p.ne(&0))? as i128;
+                let bps = (price as i128 - prior_price) * 10_000 / prior_price.abs();
+                Some(bps.clamp(i64::MIN as i128, i64::MAX as i128) as i64)
+            }
+        }
+    }
+}
+
+/// Returns true if a price's confidence interval is within the given width, in basis points of the price.
+pub fn is_price_confident(price: i64, conf: u64, max_confidence_bps: Option<u64>) -> bool {
+    max_confidence_bps.map_or(true, |max_confidence_bps| {
+        (conf as u128) * 10_000 <= (price.unsigned_abs() as u128) * (max_confidence_bps as u128)
+    })
+}
+
+/// The oracle programs a thread can read prices from.
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum OracleSource {
+    /// A Pyth price account.
+    Pyth,
+    /// A Switchboard V2 aggregator account.
+    Switchboard,
+}
+
+/// The account discriminator of a Switchboard V2 aggregator.
+const SWITCHBOARD_AGGREGATOR_DISCRIMINATOR: [u8; 8] = [217, 230, 65, 101, 201, 162, 27, 125];
+
+/// Byte offsets of the aggregator fields read from a Switchboard V2 aggregator account.
+const SWITCHBOARD_MIN_ORACLE_RESULTS_OFFSET: usize = 236;
+const SWITCHBOARD_NUM_SUCCESS_OFFSET: usize = 341;
+const SWITCHBOARD_ROUND_OPEN_TIMESTAMP_OFFSET: usize = 358;
+const SWITCHBOARD_RESULT_OFFSET: usize = 366;
+const SWITCHBOARD_STD_DEVIATION_OFFSET: usize = 386;
+
+impl OracleSource {
+    /// Reads the latest price from a price feed account's data.
+    /// Returns None if the data is not a price feed of this oracle, or if the feed has no valid price.
+    pub fn load_price(&self, data: &[u8]) -> Option<OraclePrice> {
+        match self {
+            OracleSource::Pyth => {
+                let price = pyth_sdk_solana::state::load_price_account(data)
+                    .ok()?
+                    .to_price_feed(&Pubkey::default())
+                    .get_price_unchecked();
+                let (multiplier, scale) = match u32::try_from(price.expo) {
+                    Ok(expo) => (10i128.checked_pow(expo)?, 0),
+                    Err(_) => (1, price.expo.unsigned_abs()),
+                };
+                Some(OraclePrice {
+                    price: (price.price as i128).checked_mul(multiplier)?,
+                    conf: (price.conf as u128).checked_mul(multiplier as u128)?,
+                    scale,
+                    publish_time: price.publish_time,
+                })
+            }
+            OracleSource::Switchboard => {
+                if data.len() < SWITCHBOARD_STD_DEVIATION_OFFSET + 20
+                    || !data[..8].eq(&SWITCHBOARD_AGGREGATOR_DISCRIMINATOR)
+                {
+                    return None;
+                }
+
+                // The account length is checked above, so these reads are in bounds.
+                let read = |offset: usize, len: usize| &data[offset..offset + len];
+                let read_u32 =
+                    |offset: usize| u32::from_le_bytes(read(offset, 4).try_into().unwrap());
+                let read_decimal = |offset: usize| {
+                    (
+                        i128::from_le_bytes(read(offset, 16).try_into().unwrap()),
+                        read_u32(offset + 16),
+                    )
+                };
+
+                // The latest round only holds a valid result if enough oracles responded.
+                let min_oracle_results = read_u32(SWITCHBOARD_MIN_ORACLE_RESULTS_OFFSET);
+                let num_success = read_u32(SWITCHBOARD_NUM_SUCCESS_OFFSET);
+                if num_success.eq(&0) || num_success.lt(&min_oracle_results) {
+                    return None;
+                }
+
+                // Rescale the standard deviation to the result's scale.
+                let (price, scale) = read_decimal(SWITCHBOARD_RESULT_OFFSET);
+                let (std_deviation, std_deviation_scale) =
+                    read_decimal(SWITCHBOARD_STD_DEVIATION_OFFSET);
+                let conf = OraclePrice::rescale(std_deviation, std_deviation_scale, scale)?;
+                Some(OraclePrice {
+                    price,
+                    conf: conf.unsigned_abs(),
+                    scale,
+                    publish_time: i64::from_le_bytes(
+                        read(SWITCHBOARD_ROUND_OPEN_TIMESTAMP_OFFSET, 8)
+                            .try_into()
+                            .unwrap(),
+                    ),
+                })
+            }
+        }
+    }
+}
+
+/// A price read from an oracle, as a decimal with the given number of decimal places.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OraclePrice {
+    /// The price, scaled by 10^scale.
+    pub price: i128,
+    /// The confidence interval of the price, scaled by 10^scale.
+    pub conf: u128,
+    /// The number of decimal places of the price and confidence interval.
+    pub scale: u32,
+    /// The unix timestamp the price was published at.
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    /// Returns true if the price was published no more than the given number of seconds before now.
+    pub fn is_fresh(&self, now: i64, max_staleness: u64) -> bool {
+        now.saturating_sub(self.publish_time) as i128 <= max_staleness as i128
+    }
+
+    /// Returns the price and confidence interval, scaled to the given number of decimals.
+    /// Returns None if either does not fit.
+    pub fn scaled(&self, decimals: u32) -> Option<(i64, u64)> {
+        let price = Self::rescale(self.price, self.scale, decimals)?;
+        let conf = Self::rescale(i128::try_from(self.conf).ok()?, self.scale, decimals)?;
+        Some((i64::try_from(price).ok()?, u64::try_from(conf).ok()?))
+    }
+
+    /// Converts a value with `from` decimal places to `to` decimal places, truncating any extra precision.
+    fn rescale(value: i128, from: u32, to: u32) -> Option<i128> {
+        if to >= from {
+            value.checked_mul(10i128.checked_pow(to - from)?)
+        } else {
+            Some(
+                10i128
+                    .checked_pow(from - to)
+                    .map_or(0, |divisor| value / divisor),
+            )
+        }
+    }
+}
+
+/// A numeric value stored as little-endian bytes in an account's data.
+#[derive(
+    AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd,
+)]
+pub enum DataValue {
+    U64(u64),
+    I64(i64),
+    U128(u128),
+}
+
+impl DataValue {
+    /// Reads a value of the same type as this one from the account data at the given byte offset.
+    /// Returns None if the account data is too short.
+    pub fn read(&self, data: &[u8], offset: u64) -> Option<DataValue> {
+        let offset = usize::try_from(offset).ok()?;
+        let bytes = |size: usize| data.get(offset..offset.checked_add(size)?);
+        match self {
+            DataValue::U64(_) => Some(DataValue::U64(u64::from_le_bytes(
+                bytes(8)?.try_into().ok()?,
+            ))),
+            DataValue::I64(_) => Some(DataValue::I64(i64::from_le_bytes(
+                bytes(8)?.try_into().ok()?,
+            ))),
+            DataValue::U128(_) => Some(DataValue::U128(u128::from_le_bytes(
+                bytes(16)?.try_into().ok()?,
+            ))),
+        }
+    }
+}
+
+/// Computes the hash of the monitored slice of an account's data, as recorded by account triggers.
+/// The hash is SHA-256, so it can be reproduced off-chain. The slice is truncated at the end of the data.
+pub fn hash_account_data(data: &[u8], offset: u64, size: u64) -> [u8; 32] {
+    let start = (offset as usize).min(data.len());
+    let end = start.saturating_add(size as usize).min(data.len());
+    solana_program::hash::hash(&data[start..end]).to_bytes()
+}
+
+/// Computes the hash of the watched components of an account, as recorded by account component triggers.
+/// Each component is prefixed with its length, so data slices truncated at the end of the data can't collide.
+pub fn hash_account_components(
+    data: &[u8],
+    lamports: u64,
+    owner: &Pubkey,
+    components: &[AccountComponent],
+) -> [u8; 32] {
+    let lamports = lamports.to_le_bytes();
+    let parts: Vec<&[u8]> = components
+        .iter()
+        .map(|component| match component {
+            AccountComponent::Data { offset, size } => {
+                let start = (*offset as usize).min(data.len());
+                let end = start.saturating_add(*size as usize).min(data.len());
+                &data[start..end]
+            }
+            AccountComponent::Lamports => &lamports,
+            AccountComponent::Owner => owner.as_ref(),
+        })
+        .collect();
+    let lengths: Vec<[u8; 8]> = parts
+        .iter()
+        .map(|part| (part.len() as u64).to_le_bytes())
+        .collect();
+    let vals: Vec<&[u8]> = parts
+        .iter()
+        .zip(lengths.iter())
+        .flat_map(|(part, length)| [length.as_ref(), *part])
+        .collect();
+    solana_program::hash::hashv(&vals).to_bytes()
+}
+
+/// The SPL Token program ID.
+pub static TOKEN_PROGRAM_ID: Pubkey = static_pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+/// The SPL Token-2022 program ID.
+pub static TOKEN_2022_PROGRAM_ID: Pubkey =
+    static_pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// The byte size of a token account without Token-2022 extensions.
+const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// The Token-2022 account type tag of token accounts, stored after the base account when it has extensions.
+const TOKEN_ACCOUNT_TYPE: u8 = 2;
+
+/// Byte offsets of the token account fields read by token balance triggers.
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+const TOKEN_ACCOUNT_STATE_OFFSET: usize = 108;
+
+/// Reads the balance of an SPL Token or Token-2022 account from its data.
+/// Returns None if the data is not an initialized token account.
+pub fn read_token_amount(data: &[u8]) -> Option<u64> {
+    let is_token_account = data.len().eq(&TOKEN_ACCOUNT_LEN)
+        || data.get(TOKEN_ACCOUNT_LEN).eq(&Some(&TOKEN_ACCOUNT_TYPE));
+    if !is_token_account || data[TOKEN_ACCOUNT_STATE_OFFSET].eq(&0) {
+        return None;
+    }
+    Some(u64::from_le_bytes(
+        data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8]
+            .try_into()
+            .ok()?,
+    ))
 }
 
 /// A response value target programs can return to update the thread.
-#[derive(AnchorDeserialize, AnchorSerialize, Clone, Debug)]
+#[derive(AnchorSerialize, Clone, Debug)]
 pub struct ThreadResponse {
     /// If set, the thread will automatically close and return lamports to the provided address.
-    /// If dynamic_instruction is also set, close_to will take precedence and the dynamic instruction will not be executed.
+    /// If dynamic instructions are also set, close_to will take precedence and the dynamic instructions will not be executed.
     pub close_to: Option<Pubkey>,
     /// A dynamic instruction to execute next.
     /// If close_to is also set, it will take precedence and the dynamic instruction will not be executed.
     pub dynamic_instruction: Option<SerializableInstruction>,
     /// Value to update the thread trigger to.
     pub trigger: Option<Trigger>,
+    /// Additional dynamic instructions to execute, in order, after the dynamic instruction.
+    /// If close_to is also set, it will take precedence and the dynamic instructions will not be executed.
+    pub dynamic_instructions: Vec<SerializableInstruction>,
+    /// Bytes to write to the start of the thread's memory.
+    pub memory: Option<Vec<u8>>,
+}
+
+/// The deserializer is written by hand so that responses from programs built before
+/// `dynamic_instructions` and `memory` were added can still be parsed.
+impl AnchorDeserialize for ThreadResponse {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let close_to = <Option<Pubkey> as AnchorDeserialize>::deserialize(buf)?;
+        let dynamic_instruction =
+            <Option<SerializableInstruction> as AnchorDeserialize>::deserialize(buf)?;
+        let trigger = <Option<Trigger> as AnchorDeserialize>::deserialize(buf)?;
+        let dynamic_instructions = if buf.is_empty() {
+            vec![]
+        } else {
+            <Vec<SerializableInstruction> as AnchorDeserialize>::deserialize(buf)?
+        };
+        let memory = if buf.is_empty() {
+            None
+        } else {
+            <Option<Vec<u8>> as AnchorDeserialize>::deserialize(buf)?
+        };
+        Ok(ThreadResponse {
+            close_to,
+            dynamic_instruction,
+            trigger,
+            dynamic_instructions,
+            memory,
+        })
+    }
 }
 
 impl Default for ThreadResponse {
@@ -117,6 +669,8 @@ impl Default for ThreadResponse {
             close_to: None,
             dynamic_instruction: None,
             trigger: None,
+            dynamic_instructions: vec![],
+            memory: None,
         };
     }
 }
@@ -188,6 +742,57 @@ impl TryFrom<Vec<u8>> for SerializableInstruction {
     }
 }
 
+/// A value a thread writes into one of its instructions' data at the given offset before executing it.
+#[derive(
+    AnchorDeserialize,
+    AnchorSerialize,
+    Serialize,
+    Deserialize,
+    BorshSchema,
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+)]
+pub struct DataPatch {
+    /// The byte offset of the instruction data to write the value at.
+    pub offset: u64,
+    /// The value to write.
+    pub value: PatchValue,
+}
+
+/// The values a thread can write into an instruction's data.
+/// Numbers are written as 8 little-endian bytes and pubkeys as 32 bytes.
+#[derive(
+    AnchorDeserialize,
+    AnchorSerialize,
+    Serialize,
+    Deserialize,
+    BorshSchema,
+    Clone,
+    Debug,
+    Hash,
+    PartialEq,
+)]
+pub enum PatchValue {
+    /// The thread's own pubkey.
+    ThreadPubkey,
+    /// The index of the instruction being executed in the thread's instruction set (u64).
+    ExecIndex,
+    /// The number of runs the thread has completed (u64).
+    RunsCompleted,
+    /// The current slot (u64).
+    Slot,
+    /// The current unix timestamp (i64).
+    UnixTimestamp,
+    /// The moment or boundary the trigger fired at, e.g. a cron or timestamp trigger's threshold (i64).
+    TriggerStartedAt,
+    /// The price that fired a pyth or oracle trigger (i64).
+    TriggerPrice,
+    /// The balance that fired a token balance trigger (u64).
+    TriggerAmount,
+}
+
 /// Account metadata needed to execute an instruction on Solana.
 #[derive(
     AnchorDeserialize,
@@ -228,3 +833,321 @@ impl SerializableAccount {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(AnchorSerialize)]
+    struct LegacyInstruction {
+        program_id: Pubkey,
+        accounts: Vec<SerializableAccount>,
+        data: Vec<u8>,
+    }
+
+    #[derive(AnchorSerialize)]
+    struct LegacyThreadResponse {
+        close_to: Option<Pubkey>,
+        dynamic_instruction: Option<LegacyInstruction>,
+        trigger: Option<Trigger>,
+    }
+
+    fn legacy_instruction() -> LegacyInstruction {
+        LegacyInstruction {
+            program_id: THREAD_PUBKEY,
+            accounts: vec![SerializableAccount::mutable(PAYER_PUBKEY, true)],
+            data: vec![1, 2, 3],
+        }
+    }
+
+    #[test]
+    fn test_legacy_instruction_deserializes() {
+        let data = legacy_instruction().try_to_vec().unwrap();
+        let instruction = SerializableInstruction::try_from_slice(&data).unwrap();
+        assert_eq!(
+            instruction,
+            SerializableInstruction {
+                program_id: THREAD_PUBKEY,
+                accounts: vec![SerializableAccount::mutable(PAYER_PUBKEY, true)],
+                data: vec![1, 2, 3],
+            }
+        );
+    }
+
+    #[test]
+    fn test_legacy_thread_response_deserializes() {
+        let data = LegacyThreadResponse {
+            close_to: None,
+            dynamic_instruction: Some(legacy_instruction()),
+            trigger: Some(Trigger::Slot { slot: 10 }),
+        }
+        .try_to_vec()
+        .unwrap();
+        let response = ThreadResponse::try_from_slice(&data).unwrap();
+        assert_eq!(response.close_to, None);
+        assert_eq!(
+            response.dynamic_instruction.map(|ix| ix.data),
+            Some(vec![1, 2, 3])
+        );
+        assert_eq!(response.trigger, Some(Trigger::Slot { slot: 10 }));
+        assert!(response.dynamic_instructions.is_empty());
+        assert_eq!(response.memory, None);
+    }
+
+    #[test]
+    fn test_pyth_trigger_layout() {
+        // Pyth triggers keep the layout of existing threads, and configurable Pyth triggers are appended.
+        let data = Trigger::Pyth {
+            price_feed: THREAD_PUBKEY,
+            equality: Equality::LessThanOrEqual,
+            limit: 7,
+        }
+        .try_to_vec()
+        .unwrap();
+        let mut expected = vec![6];
+        expected.extend_from_slice(THREAD_PUBKEY.as_ref());
+        expected.push(1);
+        expected.extend_from_slice(&7i64.to_le_bytes());
+        assert_eq!(data, expected);
+
+        let data = Trigger::PythPrice {
+            price_feed: THREAD_PUBKEY,
+            equality: Equality::LessThanOrEqual,
+            limit: 7,
+            mode: PythMode::Relative,
+            max_staleness: 30,
+            max_confidence_bps: Some(50),
+        }
+        .try_to_vec()
+        .unwrap();
+        assert_eq!(data[0], 18);
+    }
+
+    #[test]
+    fn test_equality_is_satisfied() {
+        assert!(Equality::GreaterThanOrEqual.is_satisfied(&10, &10, None));
+        assert!(!Equality::GreaterThanOrEqual.is_satisfied(&9, &10, None));
+        assert!(Equality::LessThanOrEqual.is_satisfied(&10, &10, None));
+        assert!(!Equality::LessThanOrEqual.is_satisfied(&11, &10, None));
+        assert!(Equality::Equal.is_satisfied(&10, &10, None));
+        assert!(Equality::NotEqual.is_satisfied(&9, &10, None));
+
+        // Crossing operators fire on their first observation, or once the prior value was on the other side.
+        assert!(Equality::CrossesUp.is_satisfied(&10, &10, None));
+        assert!(Equality::CrossesUp.is_satisfied(&10, &10, Some(&9)));
+        assert!(!Equality::CrossesUp.is_satisfied(&11, &10, Some(&10)));
+        assert!(Equality::CrossesDown.is_satisfied(&9, &10, None));
+        assert!(Equality::CrossesDown.is_satisfied(&9, &10, Some(&10)));
+        assert!(!Equality::CrossesDown.is_satisfied(&8, &10, Some(&9)));
+    }
+
+    #[test]
+    fn test_equality_is_rearmed() {
+        assert!(Equality::CrossesUp.is_rearmed(&9, &10, Some(&10)));
+        assert!(!Equality::CrossesUp.is_rearmed(&9, &10, Some(&9)));
+        assert!(!Equality::CrossesUp.is_rearmed(&9, &10, None));
+        assert!(Equality::CrossesDown.is_rearmed(&10, &10, Some(&9)));
+        assert!(!Equality::CrossesDown.is_rearmed(&10, &10, Some(&10)));
+
+        // Only crossing operators are re-armed.
+        assert!(!Equality::GreaterThanOrEqual.is_rearmed(&9, &10, Some(&10)));
+    }
+
+    #[test]
+    fn test_data_value_read() {
+        let mut data = vec![0xff; 4];
+        data.extend(7u64.to_le_bytes());
+        data.extend((-7i64).to_le_bytes());
+        assert_eq!(Some(DataValue::U64(7)), DataValue::U64(0).read(&data, 4));
+        assert_eq!(Some(DataValue::I64(-7)), DataValue::I64(0).read(&data, 12));
+        let value = u128::from_le_bytes(data[4..20].try_into().unwrap());
+        assert_eq!(
+            Some(DataValue::U128(value)),
+            DataValue::U128(0).read(&data, 4)
+        );
+
+        // Reads past the end of the data return None.
+        assert_eq!(None, DataValue::U64(0).read(&data, 13));
+        assert_eq!(None, DataValue::U128(0).read(&data, 5));
+        assert_eq!(None, DataValue::U64(0).read(&data, u64::MAX));
+    }
+
+    /// Builds a Switchboard aggregator account by writing each field of its layout in order,
+    /// up to the latest confirmed round's standard deviation.
+    fn switchboard_aggregator(
+        min_oracle_results: u32,
+        num_success: u32,
+        result: (i128, u32),
+        std_deviation: (i128, u32),
+    ) -> Vec<u8> {
+        let mut data = SWITCHBOARD_AGGREGATOR_DISCRIMINATOR.to_vec();
+        data.extend([0; 32 + 128 + 32 + 32]); // name, metadata, reserved, queue pubkey
+        data.extend(0u32.to_le_bytes()); // oracle_request_batch_size
+        data.extend(min_oracle_results.to_le_bytes());
+        data.extend([0; 4 + 4 + 8]); // min_job_results, min_update_delay_seconds, start_after
+        data.extend([0; 20]); // variance_threshold
+        data.extend([0; 8 + 8 + 8 + 8]); // force_report_period, expiration, consecutive_failure_count, next_allowed_update_time
+        data.extend([0; 1 + 32]); // is_locked, crank_pubkey
+        data.extend(num_success.to_le_bytes());
+        data.extend([0; 4 + 1 + 8]); // num_error, is_closed, round_open_slot
+        data.extend(1_700_000_000i64.to_le_bytes()); // round_open_timestamp
+        for (mantissa, scale) in [result, std_deviation] {
+            data.extend(mantissa.to_le_bytes());
+            data.extend(scale.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn test_switchboard_load_price() {
+        let data = switchboard_aggregator(2, 3, (123_456, 3), (5, 1));
+        assert_eq!(SWITCHBOARD_STD_DEVIATION_OFFSET + 20, data.len());
+        assert_eq!(
+            Some(OraclePrice {
+                price: 123_456,
+                conf: 500,
+                scale: 3,
+                publish_time: 1_700_000_000,
+            }),
+            OracleSource::Switchboard.load_price(&data)
+        );
+    }
+
+    #[test]
+    fn test_switchboard_load_price_invalid() {
+        // The latest round does not have enough oracle results.
+        let data = switchboard_aggregator(2, 1, (123_456, 3), (5, 1));
+        assert_eq!(None, OracleSource::Switchboard.load_price(&data));
+
+        // The account is not an aggregator, or is too short.
+        let mut data = switchboard_aggregator(2, 3, (123_456, 3), (5, 1));
+        assert_eq!(
+            None,
+            OracleSource::Switchboard.load_price(&data[..data.len() - 1])
+        );
+        data[0] = 0;
+        assert_eq!(None, OracleSource::Switchboard.load_price(&data));
+    }
+
+    fn token_account(amount: u64, state: u8, len: usize) -> Vec<u8> {
+        let mut data = vec![0; len];
+        data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8]
+            .copy_from_slice(&amount.to_le_bytes());
+        data[TOKEN_ACCOUNT_STATE_OFFSET] = state;
+        data
+    }
+
+    #[test]
+    fn test_read_token_amount() {
+        assert_eq!(
+            Some(42),
+            read_token_amount(&token_account(42, 1, TOKEN_ACCOUNT_LEN))
+        );
+
+        // Token-2022 accounts with extensions are marked with their account type.
+        let mut data = token_account(42, 1, TOKEN_ACCOUNT_LEN + 8);
+        data[TOKEN_ACCOUNT_LEN] = TOKEN_ACCOUNT_TYPE;
+        assert_eq!(Some(42), read_token_amount(&data));
+    }
+
+    #[test]
+    fn test_read_token_amount_invalid() {
+        // Uninitialized token accounts have no balance.
+        assert_eq!(
+            None,
+            read_token_amount(&token_account(42, 0, TOKEN_ACCOUNT_LEN))
+        );
+
+        // Token-2022 accounts of another type, e.g. mints, are not token accounts.
+        let mut data = token_account(42, 1, TOKEN_ACCOUNT_LEN + 8);
+        data[TOKEN_ACCOUNT_LEN] = 1;
+        assert_eq!(None, read_token_amount(&data));
+
+        // Accounts shorter than a token account are not token accounts.
+        assert_eq!(None, read_token_amount(&[1; 82]));
+    }
+
+    #[test]
+    fn test_hash_account_data() {
+        let data = [1, 2, 3, 4, 5];
+        assert_eq!(
+            solana_program::hash::hash(&[2, 3]).to_bytes(),
+            hash_account_data(&data, 1, 2)
+        );
+
+        // The slice is truncated at the end of the data.
+        assert_eq!(
+            hash_account_data(&data, 3, 2),
+            hash_account_data(&data, 3, 100)
+        );
+        assert_eq!(
+            solana_program::hash::hash(&[]).to_bytes(),
+            hash_account_data(&data, 100, 2)
+        );
+    }
+
+    #[test]
+    fn test_hash_account_components() {
+        let data = [1, 2, 3, 4, 5];
+        let owner = Pubkey::new_unique();
+        let components = [
+            AccountComponent::Data { offset: 3, size: 4 },
+            AccountComponent::Lamports,
+            AccountComponent::Owner,
+        ];
+        let hash = hash_account_components(&data, 10, &owner, &components);
+
+        // Each component is prefixed with its length, and truncated data slices keep their actual length.
+        let expected = solana_program::hash::hashv(&[
+            &2u64.to_le_bytes(),
+            &[4, 5],
+            &8u64.to_le_bytes(),
+            &10u64.to_le_bytes(),
+            &32u64.to_le_bytes(),
+            owner.as_ref(),
+        ]);
+        assert_eq!(expected.to_bytes(), hash);
+
+        // A change to any watched component changes the hash, and changes elsewhere do not.
+        assert_ne!(
+            hash,
+            hash_account_components(&data, 11, &owner, &components)
+        );
+        assert_ne!(
+            hash,
+            hash_account_components(&data, 10, &Pubkey::new_unique(), &components)
+        );
+        assert_ne!(
+            hash,
+            hash_account_components(&[1, 2, 3, 4, 6], 10, &owner, &components)
+        );
+        assert_eq!(
+            hash,
+            hash_account_components(&[0, 0, 0, 4, 5], 10, &owner, &components)
+        );
+    }
+
+    #[test]
+    fn test_thread_signer_placeholder() {
+        for index in [0, 1, 3, u8::MAX] {
+            let placeholder = thread_signer_placeholder(index);
+            assert_eq!(Some(index), thread_signer_index(&placeholder));
+            assert!(
+                ![PAYER_PUBKEY, THREAD_PUBKEY, WORKER_PUBKEY, FEE_PUBKEY].contains(&placeholder)
+            );
+        }
+    }
+
+    #[test]
+    fn test_thread_signer_index_other_pubkeys() {
+        for pubkey in [
+            PAYER_PUBKEY,
+            THREAD_PUBKEY,
+            WORKER_PUBKEY,
+            FEE_PUBKEY,
+            Pubkey::new_unique(),
+        ] {
+            assert_eq!(None, thread_signer_index(&pubkey));
+        }
+    }
+}